@@ -0,0 +1,10527 @@
+use clap::{Arg, ArgAction, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
+use itertools::{EitherOrBoth, Itertools};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use unicode_width::UnicodeWidthStr;
+
+use chrono::prelude::*;
+use chrono_tz::Tz;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+pub struct Arguments {
+    /// Display a specific year, quarter, or month.
+    ///
+    /// Examples: 2024, 24, Q1, 24Q1, 2024Q1-Q3, FY2024, FY24, FYQ2, FY2024Q1, FY24Q1
+    ///
+    /// A quarter can be shifted by a number of quarters, e.g. `FY24Q2+1` or `Q1-1`.
+    ///
+    /// A signed number of months is relative to the current month, e.g. `+2`. Negative offsets
+    /// look like flags (`-1` is `--one` and `-3` is `--three`, in either `--compat` mode), so
+    /// they need to follow `--`, e.g. `cal -- -1` for the previous month.
+    ///
+    /// A span of days can be given as `2024-06-01..2024-08-31`, or as `06-01..08-31` for the
+    /// current year. Periods named in the config file (e.g. `summer`, or `2023summer` for a
+    /// specific year) expand to their span.
+    ///
+    /// Disables usage of `--year` and `--month` flags.
+    ///
+    /// Multiple values can be given to display several (possibly non-contiguous) spans together,
+    /// e.g. `2024-01 2024-06 2024-12`. `--months-before` and `--months-after` apply to each.
+    #[arg(value_parser = parse_date_input, conflicts_with_all = ["year", "month"])]
+    date_input: Vec<DateInput>,
+
+    /// Sets the first day of the week. If not set, defaults to the system preference.
+    #[arg(short, long, value_enum)]
+    first_day_of_week: Option<FirstDayOfWeek>,
+
+    /// Sets the first day of the week by number, counting days from Monday: `0` is Monday and `6`
+    /// is Sunday (the ISO weekday minus one). Like `--first-day-of-week`, only weeks starting on
+    /// Monday or Sunday are supported.
+    #[arg(
+        long,
+        value_name = "OFFSET",
+        value_parser = parse_week_start_offset,
+        conflicts_with = "first_day_of_week"
+    )]
+    week_start_offset: Option<FirstDayOfWeek>,
+
+    /// The year to display.
+    #[arg(short, long, conflicts_with = "date_input")]
+    year: Option<i32>,
+
+    /// The month to display.
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=12), conflicts_with = "date_input", requires = "year")]
+    month: Option<u32>,
+
+    /// The first month (YYYY-MM) of a range of `--num-months` consecutive months.
+    #[arg(
+        long,
+        value_name = "MONTH",
+        value_parser = parse_month_input,
+        requires = "num_months",
+        conflicts_with_all = ["date_input", "year", "month", "whole_year", "diff", "week_of"]
+    )]
+    start_date: Option<DateInput>,
+
+    /// The number of months to display, starting at `--start-date`.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "start_date"
+    )]
+    num_months: Option<u32>,
+
+    /// Display the month containing the given date (YYYY-MM-DD), highlighting its header, along
+    /// with `--radius` months on either side.
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = parse_iso_date,
+        conflicts_with_all = [
+            "date_input", "year", "month", "start_date", "whole_year", "months_after",
+            "months_before", "three", "diff", "week_of"
+        ]
+    )]
+    around: Option<NaiveDate>,
+
+    /// The number of months to display before and after the `--around` month.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=12),
+        requires = "around"
+    )]
+    radius: Option<u32>,
+
+    /// Display the whole year containing the current (or given) month.
+    #[arg(long, conflicts_with_all = ["month", "diff", "week_of"])]
+    whole_year: bool,
+
+    /// Display from the start of the current year through today.
+    #[arg(long, conflicts_with_all = ["date_input", "year", "month", "diff", "week_of"])]
+    ytd: bool,
+
+    /// Display from the start of the current quarter through today.
+    #[arg(long, conflicts_with_all = ["date_input", "year", "month", "diff", "week_of", "ytd"])]
+    qtd: bool,
+
+    /// Use fiscal years (see `--fiscal-year-start`) for `--year`, `--ytd`, and `--qtd`, and to label
+    /// the sections of `--group-by-quarter` and the quarters noted by `--range-summary`.
+    ///
+    /// Months are the same in either kind of year, so `cal --fiscal 2024-03` displays March 2024,
+    /// labeled as part of Q3 FY2024.
+    #[arg(long)]
+    fiscal: bool,
+
+    /// The kind of year used for dates without an `FY` prefix, e.g. with `fiscal`, `cal Q1` and
+    /// `cal 2024Q1` are fiscal quarters and `cal 2024` is fiscal year 2024. Defaults to
+    /// `calendar`, or the config file's `default_year_style`.
+    #[arg(long, value_enum, value_name = "STYLE")]
+    default_year_style: Option<YearStyle>,
+
+    /// Display the number of months after the current month.
+    #[arg(short = 'A', long, value_parser = clap::value_parser!(u32).range(1..))]
+    months_after: Option<u32>,
+
+    /// Display the number of months before the current month.
+    #[arg(short = 'B', long, value_parser = clap::value_parser!(u32).range(1..))]
+    months_before: Option<u32>,
+
+    /// Display the previous, current, and next month (the same as `-B 1 -A 1`).
+    #[arg(short = '3', long, conflicts_with_all = ["months_after", "months_before"])]
+    three: bool,
+
+    /// Display only the current (or given) month, ignoring `--context`.
+    #[arg(short = '1', long, conflicts_with_all = ["months_after", "months_before", "three"])]
+    one: bool,
+
+    /// When no date is given, also display this many months before and after the current month.
+    /// `--months-before` and `--months-after` take precedence.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=12))]
+    context: Option<u32>,
+
+    /// Enable or disable colored output. Defaults to `auto`, or the config file's `color`.
+    ///
+    /// The `FORCE_COLOR` and `NO_COLOR` environment variables take precedence, in that order.
+    #[arg(
+            long,
+            require_equals = true,
+            value_name = "WHEN",
+            num_args = 0..=1,
+            default_missing_value = "always",
+            value_enum
+        )]
+    color: Option<ColorWhen>,
+
+    /// The number of colors to use, so that themes degrade gracefully on limited terminals. If not
+    /// set, it is detected from `COLORTERM` and `TERM`.
+    #[arg(long, value_enum, value_name = "DEPTH")]
+    color_depth: Option<ColorDepth>,
+
+    /// Show the year once above the months instead of in every month header, when all of the
+    /// displayed months share the same year.
+    #[arg(long)]
+    show_year_only_once: bool,
+
+    /// Append the number of days displayed to each month's header, e.g. `February 2024 (29)`.
+    /// The count is left off headers that would no longer fit within the month's width.
+    #[arg(long)]
+    show_day_count_per_month: bool,
+
+    /// Note the ISO week-year in the header of months with days in a week of another ISO year,
+    /// e.g. `Dec 2024 / 2025-W01`, to go with `--append-weeknum-suffix`. The month name is
+    /// abbreviated if needed to fit, and the note is left off if it still doesn't.
+    #[arg(long)]
+    show_iso_week_year_in_header: bool,
+
+    /// How each month's header is written.
+    #[arg(long, value_enum, default_value_t = HeaderFormat::Name)]
+    header_format: HeaderFormat,
+
+    /// Draw a rule between the weekday header and the first week.
+    #[arg(long, value_enum, value_name = "STYLE", num_args = 0..=1, default_missing_value = "line")]
+    header_rule: Option<HeaderRule>,
+
+    /// Highlight the nth occurrence of a weekday in each displayed month (e.g. `2:tue` for the
+    /// second Tuesday). Can be repeated.
+    #[arg(long, value_name = "N:WEEKDAY", value_parser = parse_nth_weekday)]
+    highlight_nth_weekday: Vec<NthWeekday>,
+
+    /// Highlight this day of every displayed month (e.g. `1` for the 1st). Can be repeated.
+    /// Months without that day (e.g. the 31st in February) are left unmarked. Without color the
+    /// day is bracketed (e.g. `[ 1]`).
+    #[arg(
+        long,
+        visible_alias = "highlight-day",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=31)
+    )]
+    highlight_day_of_month: Vec<u32>,
+
+    /// The color of highlighted days, instead of the theme's style: a 256-color palette index
+    /// (e.g. `208`) or an RGB hex value (e.g. `#ff8800`). Colors are approximated when they are
+    /// beyond the `--color-depth`.
+    #[arg(long, value_name = "COLOR", value_parser = parse_color)]
+    highlight_color: Option<Color>,
+
+    /// Highlight the dates listed in FILE, one per line as `YYYY-MM-DD[,COLOR][,LABEL]` (e.g.
+    /// `2024-03-15,#ff0000,Taxes due`). COLOR is written the same way as for `--highlight-color`,
+    /// and labeled dates are listed below the calendar. Blank lines and lines starting with `#`
+    /// are ignored.
+    #[arg(long, value_name = "FILE")]
+    highlight_file: Option<PathBuf>,
+
+    /// Mark the dates listed in FILE (written the same way as for `--highlight-file`) and print
+    /// each month's labels beside it, one per line starting next to the month's header. Months
+    /// are stacked in a single column to make room, and labels that don't fit beside a month
+    /// continue below it.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["columns", "group_by_quarter", "continuous", "diff"]
+    )]
+    overlay_notes: Option<PathBuf>,
+
+    /// Display exactly N week rows for every month, leaving extra rows blank or hiding the weeks
+    /// after the Nth (with a warning, unless `--quiet`).
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=6))]
+    force_weeks: Option<u32>,
+
+    /// Leave out the blank week rows at the bottom of a row of months. By default every row of
+    /// months is as tall as the longest month displayed, so that rows line up.
+    #[arg(long)]
+    no_empty_trailing_row: bool,
+
+    /// The number of months to display per row.
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..=12))]
+    columns: u32,
+
+    /// Draw a divider between rows of months that fall on a quarter boundary.
+    ///
+    /// Dividers are only drawn between rows, so quarter boundaries that fall within a row (e.g.
+    /// with `--columns 2` or `--columns 4`) are not marked.
+    #[arg(long)]
+    quarter_dividers: bool,
+
+    /// Group the months into quarters, each displayed as its own row under a section header (e.g.
+    /// `Q1 2024`, or `Q1 FY2024` with `--fiscal`). Overrides `--columns`.
+    #[arg(long)]
+    group_by_quarter: bool,
+
+    /// With `--columns 1`, print the weekday header once at the top instead of above every month.
+    #[arg(long)]
+    shared_weekday_header: bool,
+
+    /// The month (1 - 12) that fiscal years start in. Fiscal years are named after the calendar
+    /// year that they end in.
+    #[arg(long, value_name = "MONTH", default_value_t = 7, value_parser = clap::value_parser!(u32).range(1..=12))]
+    fiscal_year_start: u32,
+
+    /// Place two-digit years at or above this pivot in the previous century (e.g. with
+    /// `--year-pivot 50`, `cal 49` is 2049 and `cal 98` is 1998). By default two-digit years are
+    /// always in the current century.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(0..=99))]
+    year_pivot: Option<u32>,
+
+    /// Mark today with brackets (e.g. `[20]`) when color is not being used.
+    #[arg(long)]
+    ascii_today_marker: bool,
+
+    /// Show the ISO week number in place of each Monday's date (e.g. `W10`), instead of adding a
+    /// separate column.
+    ///
+    /// The `W` takes the space before Monday's cell, so every row gets a one character gutter on
+    /// either side, widening each month by two columns.
+    #[arg(long)]
+    append_weeknum_suffix: bool,
+
+    /// How today and highlighted dates are marked. Defaults to the theme's styles when color is
+    /// enabled. `box` brackets the dates (e.g. `[20]`) and works with or without color.
+    #[arg(long, value_enum)]
+    highlight_style: Option<HighlightStyle>,
+
+    /// Mark the week containing today: with a background color when color is enabled, otherwise
+    /// with a `>` before the row.
+    #[arg(long)]
+    highlight_current_week: bool,
+
+    /// Shade the column for today's weekday in every week, for a quick "what day is it". Only
+    /// applies when color is enabled.
+    #[arg(long)]
+    anchor_today_column: bool,
+
+    /// Render the calendar in reverse video (today is shown in normal video so it still stands
+    /// out). Only applies when color is enabled.
+    #[arg(long)]
+    invert: bool,
+
+    /// How days before today are styled, e.g. to see at a glance how much of the month is gone.
+    /// Only applies when color is enabled. Not every terminal supports `strike`.
+    #[arg(long, value_enum, default_value_t = PastStyle::None)]
+    past_style: PastStyle,
+
+    /// Fill the empty cells before the first and after the last day of each month with this
+    /// character (e.g. `·`), so the grid reads as a table.
+    ///
+    /// Only cells inside a month's own weeks are filled. The blank rows below a shorter month in a
+    /// multi-month layout are left empty.
+    #[arg(long, value_name = "CHAR")]
+    fill_empty: Option<char>,
+
+    /// Fill the empty cells before the first and after the last day of each month with the days
+    /// of the neighboring months, dimmed (or in parentheses without color, e.g. `(29)`). Takes
+    /// precedence over `--fill-empty`.
+    #[arg(long)]
+    trailing_days_from_adjacent_months: bool,
+
+    /// Show Saturday and Sunday together in a single `Sa/Su` column (e.g. ` 6/ 7`), for narrow
+    /// displays. Experimental.
+    ///
+    /// Only works when weeks start on Monday, since Sunday-first weeks put the weekend days at
+    /// opposite ends of the row. A marker on either weekend day (e.g. `--ascii-today-marker`)
+    /// brackets the whole weekend cell.
+    #[arg(long)]
+    fold_weekends_into_one_column: bool,
+
+    /// Separate months that are side by side with a tab instead of two spaces, for easier
+    /// parsing by tools that split on tabs.
+    ///
+    /// This breaks the visual alignment of the months, so it can't be combined with options
+    /// that line things up across a row (`--quarter-dividers`, `--group-by-quarter`,
+    /// `--show-year-only-once`, `--align-firsts` and `--continuous`).
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "quarter_dividers", "group_by_quarter", "show_year_only_once", "align_firsts",
+            "continuous"
+        ]
+    )]
+    use_tabs: bool,
+
+    /// How single digit days are padded to fill their two character cell.
+    #[arg(long, value_enum, default_value_t = DayPad::Space)]
+    day_pad: DayPad,
+
+    /// What each day's cell shows.
+    #[arg(long, value_enum, default_value_t = CellContent::Day)]
+    cell_content: CellContent,
+
+    /// Show weekend days as a 🎉 instead of their number. Only affects text output.
+    ///
+    /// The emoji is two columns wide, so this needs a terminal (and font) that displays emoji at
+    /// double width to keep the columns lined up.
+    #[arg(long)]
+    emoji_weekends: bool,
+
+    /// The gap between day cells. `thin` is experimental, since thin spaces aren't as wide as
+    /// other characters in every terminal font. When days are marked with plain text characters
+    /// (e.g. `--ascii-today-marker`), a regular space is used so that the markers have room.
+    #[arg(long, value_enum, default_value_t = DayGap::Normal)]
+    day_gap: DayGap,
+
+    /// A preset bundle of colors for the headers, weekends, today, and highlighted dates. Ignored
+    /// when color is disabled.
+    #[arg(long, value_enum, default_value_t = ThemeName::Default)]
+    theme: ThemeName,
+
+    /// Start each month's rows on the weekday of its 1st, so that day 1 is always in the first
+    /// column. Useful for comparing months side by side.
+    ///
+    /// Each month gets its own weekday header, so the same column can be a different weekday in
+    /// neighboring months.
+    #[arg(long)]
+    align_firsts: bool,
+
+    /// Display the weeks of the whole range as one continuous list under a single weekday header,
+    /// instead of a block per month. Rows where a month starts are labeled with that month (e.g.
+    /// `Mar 2024`). Only available as text.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "columns", "group_by_quarter", "quarter_dividers", "shared_weekday_header",
+            "align_firsts", "repeat", "diff", "week_of"
+        ]
+    )]
+    continuous: bool,
+
+    /// Display each month this many times side by side, e.g. as a base for printed planners.
+    /// Today and any highlighted dates are marked in every copy.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=12))]
+    repeat: Option<u32>,
+
+    /// Print a summary line with the total number of days and weekend days displayed. When
+    /// holidays are loaded (from the config file or `--holiday-set`), the line also counts the
+    /// holidays displayed and the business days, which leave out both weekends and holidays. With
+    /// `--fiscal`, the line also notes the fiscal quarters covered (e.g. `(Q3 FY2024)`).
+    #[arg(long)]
+    range_summary: bool,
+
+    /// Print the span of dates that would be displayed (e.g. `2024-01-01..2024-03-31`, including
+    /// any `--months-before` and `--months-after`) instead of a calendar, one line per span.
+    #[arg(
+        long,
+        visible_alias = "print-date-range",
+        conflicts_with_all = ["week_of", "weekday_of", "is_leap"]
+    )]
+    print_range: bool,
+
+    /// Print how many times the weekday (e.g. `fri` or `friday`) occurs in the dates that would be
+    /// displayed, instead of a calendar. Can be repeated for a count per weekday, one per line.
+    #[arg(
+        long,
+        value_name = "WEEKDAY",
+        value_parser = parse_weekday,
+        conflicts_with_all = ["week_of", "weekday_of", "is_leap", "print_range"]
+    )]
+    count_weekday: Vec<Weekday>,
+
+    /// Mark the holidays from a built-in set in every displayed year, the same way as the config
+    /// file's holidays (which win when both have the same date). Can be repeated.
+    #[arg(long, value_enum, value_name = "SET")]
+    holiday_set: Vec<HolidaySet>,
+
+    /// Print the holidays from a built-in set for each year that would be displayed (e.g.
+    /// `--list-holidays us-federal 2024`), instead of a calendar.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SET",
+        conflicts_with_all = ["week_of", "weekday_of", "is_leap", "print_range", "count_weekday"]
+    )]
+    list_holidays: Option<HolidaySet>,
+
+    /// Compare two months side by side, e.g. `--diff 2024-03 2024-04`.
+    ///
+    /// The header notes the weekday each month starts on, making it easy to see how the layout
+    /// shifts between them.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["MONTH", "MONTH"],
+        value_parser = parse_month_input,
+        conflicts_with_all = ["date_input", "year", "month", "months_after", "months_before"]
+    )]
+    diff: Vec<DateInput>,
+
+    /// Display only the week containing the given date (YYYY-MM-DD), as a single row beneath the
+    /// weekday header.
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = parse_iso_date,
+        conflicts_with_all = ["date_input", "year", "month", "months_after", "months_before", "diff"]
+    )]
+    week_of: Option<NaiveDate>,
+
+    /// Interpret short flags the way util-linux `cal` does: `-s` and `-m` select Sunday or Monday
+    /// as the first day of the week, and `-y` displays the whole year. `-3` and `-1` work the same
+    /// in either mode (negative relative months need `--`, e.g. `cal -- -1`). `--year` and
+    /// `--month` are still available by their long names.
+    #[arg(long, value_enum, default_value_t = Compat::Native)]
+    compat: Compat,
+
+    /// Only accept dates in their canonical forms, for predictable scripting: `YYYY`, `YYYY-MM`,
+    /// `YYYY-QN`, `FYYYYY`, `FYYYYY-QN`, `QN`, `FYQN`, `+N`/`-N`, `YYYY-MM-DD..YYYY-MM-DD` and
+    /// `MM-DD..MM-DD`.
+    ///
+    /// Rejects two-digit years (`24`, `FY24`), lowercase (`q1`, `fy2024`), compact forms
+    /// (`202401`, `2024Q1`), slashes (`2024/01`, `01/2024`), single-digit months (`2024-1`),
+    /// quarter ranges (`2024Q1-Q3`) and quarter offsets (`2024Q1+1`).
+    #[arg(long)]
+    strict: bool,
+
+    /// Exit with an error if the hash of the rendered output (in any `--format`, including the
+    /// answers to queries like `--count-weekday`) doesn't match `HASH`. Useful for detecting
+    /// unexpected rendering changes in CI.
+    ///
+    /// The hash is the 64-bit FNV-1a hash of the output with color codes removed, written as 16
+    /// hex digits. The error message includes the actual hash.
+    #[arg(long, value_name = "HASH")]
+    verify: Option<String>,
+
+    /// The time zone used to determine today's date, as an IANA name (e.g. `America/New_York`).
+    /// Defaults to the system's local time zone.
+    #[arg(long, value_name = "TZ", value_parser = parse_timezone)]
+    timezone: Option<Tz>,
+
+    /// Print the weekday of the given date (YYYY-MM-DD, or YYYY-MM for the 1st of the month)
+    /// instead of a calendar.
+    #[arg(long, value_name = "DATE", value_parser = parse_weekday_of)]
+    weekday_of: Option<NaiveDate>,
+
+    /// Print `true` or `false` depending on whether the year (the current year if not given) is a
+    /// leap year, instead of a calendar.
+    ///
+    /// Exits with status 0 for a leap year and 1 otherwise, so it can be used directly in shell
+    /// conditionals.
+    #[arg(long, value_name = "YEAR", num_args = 0..=1, allow_negative_numbers = true)]
+    is_leap: Option<Option<i32>>,
+
+    /// The output format. Calendars can be rendered as `text`, `svg` or `grid-csv`, `json` is
+    /// supported by query modes like `--weekday-of`.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Suppress the notes and warnings printed to stderr (e.g. about days hidden by
+    /// `--force-weeks`). Errors are still reported.
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Print the version along with the git commit, build date, and target it was built for,
+    /// for inclusion in bug reports.
+    #[arg(long)]
+    version_verbose: bool,
+
+    /// Write the output to FILE instead of stdout. Color is only used with `--color always`, since
+    /// the file isn't a terminal.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Refuse to display more than N months (e.g. from a mistyped `--num-months`) unless `--force`
+    /// is given.
+    #[arg(long, value_name = "N", default_value_t = 60, value_parser = clap::value_parser!(u32).range(1..))]
+    max_months: u32,
+
+    /// Display the calendar even if it has more months than `--max-months`.
+    #[arg(long)]
+    force: bool,
+
+    /// Describe on stderr how the displayed dates were chosen: the parsed and resolved date input,
+    /// the first day of the week, and the resulting date ranges.
+    #[arg(long)]
+    explain: bool,
+
+    /// Trace each step from the date input to the displayed months (parsing, resolving against
+    /// today, the date ranges, and the months built from them) on stderr. Meant for debugging
+    /// `cal` itself, see `--explain` for a summary. Takes precedence over `RUST_LOG`.
+    #[arg(long)]
+    debug: bool,
+
+    /// Holidays from the config file, marked with the theme's holiday style and listed below the
+    /// calendar.
+    #[arg(skip)]
+    holidays: BTreeMap<NaiveDate, String>,
+}
+
+/// Allows tests to build `Arguments` directly (e.g. `Arguments { months_after: Some(0),
+/// ..Default::default() }`), including combinations that can't be expressed on the command line.
+#[cfg(test)]
+impl Default for Arguments {
+    fn default() -> Self {
+        Arguments::parse_from(["cal"])
+    }
+}
+
+impl Arguments {
+    /// Returns whether the year asked about with `--is-leap` is a leap year, or `None` if
+    /// `--is-leap` wasn't given.
+    pub fn leap_year_query(&self, current_date: NaiveDate) -> Option<bool> {
+        self.is_leap
+            .map(|year| is_leap_year(year.unwrap_or(current_date.year())))
+    }
+
+    /// Returns today's date in `--timezone`, or in the system's local time zone if not given (see
+    /// [`today`]).
+    pub fn today(&self) -> NaiveDate {
+        today(self.timezone)
+    }
+
+    /// Parses the command line like `Arguments::try_parse_from`, except that dates are checked
+    /// with `--strict`'s rules when it is present, and the names of `config`'s periods are accepted
+    /// as dates.
+    pub fn try_parse_argv(args: Vec<OsString>, config: &Config) -> Result<Arguments, clap::Error> {
+        let mut command = Arguments::command();
+        let periods = config.periods.clone();
+
+        command = command.mut_arg("date_input", |arg| {
+            arg.value_parser(move |s: &str| match parse_period(&periods, s) {
+                Some(date_input) => date_input,
+                None => parse_date_input(s),
+            })
+        });
+
+        // `--compat` changes what the short flags mean, so the command line is parsed with the native
+        // meanings first and then again with util-linux's when it asks for them (or fails to parse
+        // as a native one). Both passes leave `--` and option values to clap.
+        let is_util_linux =
+            |matches: &ArgMatches| matches.get_one::<Compat>("compat") == Some(&Compat::UtilLinux);
+        let native = command.clone().try_get_matches_from(&args);
+
+        if native.as_ref().map_or(true, is_util_linux) {
+            let mut command = util_linux_command(command.clone());
+
+            match command.try_get_matches_from_mut(&args) {
+                Ok(matches) if is_util_linux(&matches) => {
+                    check_strict_date_input(&mut command, &matches, config)?;
+                    return util_linux_arguments(&mut command, matches);
+                }
+                // when neither parse succeeds, the util-linux error is the relevant one if that's
+                // the mode that was asked for
+                Err(err)
+                    if native.is_ok()
+                        || command
+                            .clone()
+                            .ignore_errors(true)
+                            .try_get_matches_from(&args)
+                            .is_ok_and(|matches| is_util_linux(&matches)) =>
+                {
+                    return Err(err)
+                }
+                _ => {}
+            }
+        }
+
+        let mut matches = native?;
+        check_strict_date_input(&mut command, &matches, config)?;
+        Arguments::from_arg_matches_mut(&mut matches)
+    }
+
+    /// Uses the `CAL_DATE` environment variable as the date input when none of the options that
+    /// pick which dates to display were given on the command line.
+    pub fn with_env(self) -> Result<Arguments, String> {
+        let selects_dates = !self.date_input.is_empty()
+            || self.year.is_some()
+            || self.month.is_some()
+            || self.start_date.is_some()
+            || self.around.is_some()
+            || self.ytd
+            || self.qtd
+            || self.week_of.is_some()
+            || !self.diff.is_empty();
+
+        let Some(value) = std::env::var_os("CAL_DATE").filter(|_| !selects_dates) else {
+            return Ok(self);
+        };
+        let value = value
+            .into_string()
+            .map_err(|value| format!("CAL_DATE is not valid UTF-8: {:?}", value))?;
+        let date_input = if self.strict {
+            parse_date_input_strict(&value)
+        } else {
+            parse_date_input(&value)
+        }
+        .map_err(|err| format!("invalid CAL_DATE: {}", err))?;
+
+        Ok(Arguments {
+            date_input: vec![date_input],
+            ..self
+        })
+    }
+
+    /// Returns the file given to `--output`, if any.
+    pub fn output_path(&self) -> Option<&Path> {
+        self.output.as_deref()
+    }
+
+    /// Returns whether `--debug` was given.
+    pub fn debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Turns off colored output, e.g. when the terminal is unable to display it. `FORCE_COLOR`
+    /// still takes precedence.
+    pub fn disable_color(self) -> Arguments {
+        Arguments {
+            color: Some(ColorWhen::Never),
+            ..self
+        }
+    }
+
+    /// Fills in any settings that weren't given on the command line from `config`.
+    pub fn with_config(self, config: &Config) -> Arguments {
+        Arguments {
+            context: self.context.or(config.context),
+            color: self.color.or(config.color),
+            default_year_style: self.default_year_style.or(config.default_year_style),
+            holidays: config
+                .holidays
+                .iter()
+                .filter_map(|(date, name)| Some((parse_iso_date(date).ok()?, name.clone())))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+/// Settings read from the config file. Anything set on the command line takes precedence.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The default for `--context`.
+    pub context: Option<u32>,
+    /// The default for `--color`.
+    pub color: Option<ColorWhen>,
+    /// The default for `--default-year-style`.
+    pub default_year_style: Option<YearStyle>,
+    /// Named periods (e.g. `summer = "06-01..08-31"`) that can be passed in place of a date.
+    pub periods: BTreeMap<String, String>,
+    /// Holidays by date (e.g. `"2024-12-25" = "Christmas"`).
+    pub holidays: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Returns the path to the config file: `$CAL_CONFIG` if set, otherwise
+    /// `~/.config/cal/config.toml`.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("CAL_CONFIG") {
+            return Some(PathBuf::from(path));
+        }
+
+        home::home_dir().map(|mut path| {
+            path.push(".config/cal/config.toml");
+            path
+        })
+    }
+
+    /// Loads the config file. A missing config file is treated as an empty config.
+    pub fn load() -> Result<Config, String> {
+        let Some(path) = Config::path() else {
+            return Ok(Config::default());
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Config::parse(&contents)
+                .map_err(|err| format!("Invalid config file {}: {}", path.display(), err)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(format!(
+                "Could not read config file {}: {}",
+                path.display(),
+                err
+            )),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Config, String> {
+        let config: Config = toml::from_str(contents).map_err(|err| err.message().to_string())?;
+
+        if let Some(context) = config.context {
+            if !(1..=12).contains(&context) {
+                return Err(format!("context must be 1 - 12, got: {}", context));
+            }
+        }
+
+        for date in config.holidays.keys() {
+            if parse_iso_date(date).is_err() {
+                return Err(format!("holidays must be dated YYYY-MM-DD, got: {}", date));
+            }
+        }
+
+        for (name, period) in &config.periods {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic() || c == '_') {
+                return Err(format!(
+                    "period names may only contain letters and underscores, got: {}",
+                    name
+                ));
+            }
+
+            match parse_date_input(period) {
+                Ok(DateInput::CurrentYearRange(..)) => {}
+                _ => {
+                    return Err(format!(
+                        "period {} must be a range like 06-01..08-31, got: {}",
+                        name, period
+                    ))
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Passes a note or warning on to `notices` (stderr, see [`print`]), unless `--quiet` was given.
+/// Anything that should stop `cal` is returned as an error instead, so that it's reported
+/// regardless.
+fn notice(quiet: bool, notices: &mut dyn FnMut(String), message: String) {
+    if !quiet {
+        notices(message);
+    }
+}
+
+/// Errors that can occur while rendering a calendar.
+#[derive(Debug, PartialEq)]
+pub enum CalError {
+    /// The requested dates extend past the range of dates that can be represented.
+    OutOfRange(String),
+    /// The requested output format isn't available for what is being printed.
+    UnsupportedFormat(String),
+    /// The rendered output didn't match the hash given to `--verify`.
+    VerificationFailed { expected: String, actual: String },
+    /// More months were requested than `--max-months` allows.
+    TooManyMonths { months: usize, max_months: u32 },
+    /// The output couldn't be written to the file given to `--output`.
+    Io { path: PathBuf, message: String },
+    /// The requested dates don't include any days (e.g. the start is after the end).
+    EmptyRange,
+    /// A file given on the command line couldn't be read.
+    Read { path: PathBuf, message: String },
+    /// Options were given that can't be used together.
+    IncompatibleOptions(String),
+    /// A date input can't be turned into a range of dates (e.g. a relative month that wasn't
+    /// resolved against the current date first).
+    InvalidDateInput(String),
+}
+
+impl fmt::Display for CalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalError::OutOfRange(requested) => write!(
+                f,
+                "{} is outside of the supported range of years ({} to {})",
+                requested,
+                NaiveDate::MIN.year(),
+                NaiveDate::MAX.year()
+            ),
+            CalError::UnsupportedFormat(message)
+            | CalError::IncompatibleOptions(message)
+            | CalError::InvalidDateInput(message) => write!(f, "{}", message),
+            CalError::VerificationFailed { expected, actual } => write!(
+                f,
+                "rendered output hash {} does not match the expected hash {}",
+                actual, expected
+            ),
+            CalError::EmptyRange => write!(f, "no months in range"),
+            CalError::TooManyMonths { months, max_months } => write!(
+                f,
+                "{} months would be displayed, which is more than --max-months {} (use --force to display them anyway)",
+                months, max_months
+            ),
+            CalError::Io { path, message } => {
+                write!(f, "could not write {}: {}", path.display(), message)
+            }
+            CalError::Read { path, message } => {
+                write!(f, "could not read {}: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalError {}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorWhen {
+    Always,
+    Auto,
+    Never,
+}
+
+impl std::fmt::Display for ColorWhen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// The 16 basic ANSI colors.
+    #[value(name = "16")]
+    Basic,
+    /// The 256-color palette.
+    #[value(name = "256")]
+    Palette,
+    /// 24-bit RGB colors.
+    Truecolor,
+}
+
+impl ColorDepth {
+    /// Guesses the terminal's color depth: `COLORTERM` advertises truecolor support, and a few
+    /// `TERM`s are known to be limited to the basic colors. Anything else is assumed to support
+    /// the 256-color palette, as nearly every terminal emulator does.
+    fn detect() -> ColorDepth {
+        if matches!(
+            std::env::var("COLORTERM").as_deref(),
+            Ok("truecolor") | Ok("24bit")
+        ) {
+            return ColorDepth::Truecolor;
+        }
+
+        match std::env::var("TERM").as_deref() {
+            Ok(term) if term.contains("256color") => ColorDepth::Palette,
+            Ok("linux" | "ansi" | "cons25") => ColorDepth::Basic,
+            Ok(term) if term.starts_with("vt") => ColorDepth::Basic,
+            _ => ColorDepth::Palette,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DayPad {
+    /// Right-align days with a leading space (` 1`).
+    Space,
+    /// Pad days with a leading zero (`01`).
+    Zero,
+    /// Left-align days with a trailing space (`1 `).
+    Left,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CellContent {
+    /// The day of the month (1 - 31).
+    Day,
+    /// The day's position in the week (1 - 7), counting from the first day of the week.
+    WeekdayIndex,
+    /// The day of the year (1 - 366). Cells are widened to three characters to fit.
+    DayOfYear,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DayGap {
+    /// A regular space.
+    Normal,
+    /// A thin space (U+2009).
+    Thin,
+    /// No gap at all.
+    None,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PastStyle {
+    /// Dim the days before today.
+    Dim,
+    /// Strike through the days before today.
+    Strike,
+    /// Style the days before today like any other day.
+    None,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    /// A monochrome SVG image, with CSS classes for styling today, weekends, and highlights.
+    Svg,
+    /// A CSV table per month, with a row per week and a column per weekday.
+    GridCsv,
+}
+
+impl OutputFormat {
+    /// The format's name, as given to `--format`.
+    fn name(&self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compat {
+    Native,
+    UtilLinux,
+}
+
+/// Gives `command`'s short flags their util-linux meanings: `-s` and `-m` select Sunday or Monday
+/// as the first day of the week, and `-y` displays the whole year. `-j` is recognized only to be
+/// rejected, see [`util_linux_arguments`].
+fn util_linux_command(command: clap::Command) -> clap::Command {
+    let flag = |id: &'static str, short: char| {
+        Arg::new(id)
+            .short(short)
+            .action(ArgAction::SetTrue)
+            .hide(true)
+    };
+
+    command
+        .mut_arg("year", |arg| arg.short(None))
+        .mut_arg("month", |arg| arg.short(None))
+        .arg(
+            flag("util_linux_sunday", 's')
+                .conflicts_with_all(["first_day_of_week", "week_start_offset"]),
+        )
+        .arg(flag("util_linux_monday", 'm').conflicts_with_all([
+            "first_day_of_week",
+            "week_start_offset",
+            "util_linux_sunday",
+        ]))
+        .arg(flag("util_linux_whole_year", 'y').conflicts_with_all(["month", "diff", "week_of"]))
+        .arg(flag("util_linux_julian", 'j'))
+}
+
+/// Builds the `Arguments` for a command line parsed by [`util_linux_command`], translating the
+/// util-linux flags into their native equivalents.
+fn util_linux_arguments(
+    command: &mut clap::Command,
+    mut matches: ArgMatches,
+) -> Result<Arguments, clap::Error> {
+    if matches.get_flag("util_linux_julian") {
+        return Err(command.error(
+            clap::error::ErrorKind::UnknownArgument,
+            "-j (Julian days) is not supported",
+        ));
+    }
+
+    let first_day_of_week = if matches.get_flag("util_linux_sunday") {
+        Some(FirstDayOfWeek::Sunday)
+    } else if matches.get_flag("util_linux_monday") {
+        Some(FirstDayOfWeek::Monday)
+    } else {
+        None
+    };
+    let whole_year = matches.get_flag("util_linux_whole_year");
+    let args = Arguments::from_arg_matches_mut(&mut matches)?;
+
+    Ok(Arguments {
+        first_day_of_week: first_day_of_week.or(args.first_day_of_week),
+        whole_year: whole_year || args.whole_year,
+        ..args
+    })
+}
+
+/// Rejects the non-canonical date inputs in `matches` when `--strict` was given. The names of
+/// `config`'s periods are always accepted.
+fn check_strict_date_input(
+    command: &mut clap::Command,
+    matches: &ArgMatches,
+    config: &Config,
+) -> Result<(), clap::Error> {
+    if !matches.get_flag("strict") {
+        return Ok(());
+    }
+
+    for value in matches.get_raw("date_input").into_iter().flatten() {
+        let value = value.to_string_lossy();
+        if parse_period(&config.periods, &value).is_some() {
+            continue;
+        }
+
+        if let Err(err) = parse_date_input_strict(&value) {
+            return Err(command.error(
+                clap::error::ErrorKind::ValueValidation,
+                format!("invalid value '{}' for '[DATE_INPUT]...': {}", value, err),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the name of one of `periods` (optionally prefixed with a year, e.g. `2023summer`) into
+/// the range it stands for, or returns `None` if `s` doesn't name a period.
+fn parse_period(periods: &BTreeMap<String, String>, s: &str) -> Option<Result<DateInput, String>> {
+    let (year, name) =
+        s.split_at(s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len());
+
+    let period = match periods.get(name) {
+        Some(period) if year.is_empty() || year.len() == 4 => period,
+        _ => return None,
+    };
+
+    Some(match (year.parse::<i32>(), parse_date_input(period)) {
+        (Ok(year), Ok(DateInput::CurrentYearRange(start, end))) => {
+            month_day_range(year, start, end)
+                .map(|(start, end)| DateInput::Range(start, end))
+                .map_err(|err| err.to_string())
+        }
+        (_, date_input) => date_input,
+    })
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeaderFormat {
+    /// The month name and year (`March 2024`).
+    Name,
+    /// The ISO year and month (`2024-03`).
+    Iso,
+    /// The ISO year and month followed by the abbreviated name (`2024-03 (Mar 2024)`). The name
+    /// is abbreviated so that the header fits within the month's width.
+    Both,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeaderRule {
+    /// A solid line (`─`).
+    Line,
+    /// ASCII dashes (`-`).
+    Dash,
+}
+
+impl HeaderRule {
+    fn char(&self) -> char {
+        match self {
+            HeaderRule::Line => '─',
+            HeaderRule::Dash => '-',
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HighlightStyle {
+    Reverse,
+    Underline,
+    Bold,
+    /// Surround the date with brackets.
+    Box,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ThemeName {
+    Default,
+    Solarized,
+    Mono,
+    HighContrast,
+}
+
+/// A pair of SGR parameter lists that turn a style on and back off again (e.g. `7` and `27` for
+/// reverse video). Empty parameters leave the text unstyled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Style {
+    pub on: String,
+    pub off: String,
+}
+
+impl Style {
+    fn sgr(on: &str, off: &str) -> Style {
+        Style {
+            on: on.to_string(),
+            off: off.to_string(),
+        }
+    }
+
+    fn none() -> Style {
+        Style::sgr("", "")
+    }
+
+    fn paint(&self, text: &str) -> String {
+        if self.on.is_empty() {
+            return text.to_string();
+        }
+
+        format!("\x1B[{}m{}\x1B[{}m", self.on, text, self.off)
+    }
+
+    /// Rewrites any 256-color or RGB colors in the style to ones that fit within `depth`.
+    fn with_depth(&self, depth: ColorDepth) -> Style {
+        let params = self.on.split(';').collect_vec();
+        let mut on = Vec::new();
+        let mut i = 0;
+
+        while i < params.len() {
+            let color = match params[i..] {
+                [base @ ("38" | "48"), "5", index, ..] => index
+                    .parse()
+                    .ok()
+                    .map(|index| (base, Color::Indexed(index), 3)),
+                [base @ ("38" | "48"), "2", r, g, b, ..] => {
+                    match (r.parse(), g.parse(), b.parse()) {
+                        (Ok(r), Ok(g), Ok(b)) => Some((base, Color::Rgb(r, g, b), 5)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+
+            match color {
+                Some((base, color, len)) => {
+                    on.push(if base == "38" {
+                        color.foreground(depth)
+                    } else {
+                        color.background(depth)
+                    });
+                    i += len;
+                }
+                None => {
+                    on.push(params[i].to_string());
+                    i += 1;
+                }
+            }
+        }
+
+        Style {
+            on: on.join(";"),
+            off: self.off.clone(),
+        }
+    }
+}
+
+/// A foreground color given on the command line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    /// An index into the 256-color palette.
+    Indexed(u8),
+    /// A 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+/// The RGB values of the basic colors, as xterm shows them.
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl Color {
+    /// Returns the SGR parameters that set this as the foreground color.
+    fn foreground(&self, depth: ColorDepth) -> String {
+        self.sgr(38, depth)
+    }
+
+    /// Returns the SGR parameters that set this as the background color.
+    fn background(&self, depth: ColorDepth) -> String {
+        self.sgr(48, depth)
+    }
+
+    /// Colors beyond `depth` are approximated: RGB colors by the nearest color in the 6x6x6
+    /// palette cube, and anything on a 16-color terminal by the nearest basic color.
+    fn sgr(&self, base: u8, depth: ColorDepth) -> String {
+        match (*self, depth) {
+            (Color::Rgb(r, g, b), ColorDepth::Truecolor) => {
+                format!("{};2;{};{};{}", base, r, g, b)
+            }
+            (color, ColorDepth::Basic) => {
+                // 30 - 37 and 90 - 97 for the foreground, 40 - 47 and 100 - 107 for the background
+                let index = color.nearest_basic();
+                let offset = if index < 8 { 0 } else { 60 - 8 };
+
+                (base - 8 + offset + index).to_string()
+            }
+            (Color::Indexed(index), _) => format!("{};5;{}", base, index),
+            (Color::Rgb(r, g, b), _) => {
+                // the cube's levels are 0, 95, 135, 175, 215, and 255
+                let level = |value: u8| match value {
+                    0..=47 => 0,
+                    48..=114 => 1,
+                    value => (value - 35) / 40,
+                };
+
+                format!(
+                    "{};5;{}",
+                    base,
+                    16 + 36 * level(r) + 6 * level(g) + level(b)
+                )
+            }
+        }
+    }
+
+    fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Indexed(index @ 0..=15) => BASIC_COLORS[index as usize],
+            Color::Indexed(index @ 16..=231) => {
+                let level = |value: u8| if value == 0 { 0 } else { 55 + 40 * value };
+                let index = index - 16;
+
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            Color::Indexed(index) => {
+                let gray = 8 + 10 * (index - 232);
+
+                (gray, gray, gray)
+            }
+        }
+    }
+
+    /// Returns the index of the closest of the 16 basic colors.
+    fn nearest_basic(&self) -> u8 {
+        if let Color::Indexed(index @ 0..=15) = *self {
+            return index;
+        }
+
+        let (r, g, b) = self.rgb();
+        let distance = |&(r2, g2, b2): &(u8, u8, u8)| {
+            let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+
+            d(r, r2) + d(g, g2) + d(b, b2)
+        };
+
+        BASIC_COLORS
+            .iter()
+            .position_min_by_key(|color| distance(color))
+            .unwrap() as u8
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+
+            return Ok(Color::Rgb(channel(0), channel(2), channel(4)));
+        }
+    } else if let Ok(index) = s.parse::<u8>() {
+        return Ok(Color::Indexed(index));
+    }
+
+    Err(format!(
+        "Invalid color (expected a palette index 0 - 255 or #RRGGBB): {}",
+        s
+    ))
+}
+
+/// A date listed in a `--highlight-file`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighlightedDate {
+    /// The color to highlight the date with, instead of the theme's highlight style.
+    pub color: Option<Color>,
+    /// A description of the date, listed below the calendar.
+    pub label: Option<String>,
+}
+
+/// Parses the contents of a `--highlight-file`. When a line has only one field after the date,
+/// it is the color if it looks like one and the label otherwise.
+fn parse_highlight_file(contents: &str) -> Result<BTreeMap<NaiveDate, HighlightedDate>, String> {
+    let mut dates = BTreeMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: String| format!("line {}: {}", index + 1, message);
+        let mut fields = line.splitn(3, ',').map(str::trim);
+        let date = parse_iso_date(fields.next().unwrap_or_default()).map_err(error)?;
+        let (color, label) = match (fields.next(), fields.next()) {
+            (None, _) => (None, None),
+            (Some(field), None) => match parse_color(field) {
+                Ok(color) => (Some(color), None),
+                Err(_) => (None, Some(field)),
+            },
+            (Some(""), Some(label)) => (None, Some(label)),
+            (Some(color), Some(label)) => (Some(parse_color(color).map_err(error)?), Some(label)),
+        };
+
+        dates.insert(
+            date,
+            HighlightedDate {
+                color,
+                label: label.filter(|label| !label.is_empty()).map(str::to_string),
+            },
+        );
+    }
+
+    Ok(dates)
+}
+
+/// The styles used for each part of the calendar when color is enabled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub header: Style,
+    pub weekday_header: Style,
+    pub weekend: Style,
+    pub today: Style,
+    /// Dates matched by `--highlight-nth-weekday`.
+    pub highlight: Style,
+    pub holiday: Style,
+    /// The row containing today, with `--highlight-current-week`.
+    pub current_week: Style,
+    /// Days from the neighboring months, with `--trailing-days-from-adjacent-months`.
+    pub adjacent_day: Style,
+    /// The column for today's weekday, with `--anchor-today-column`.
+    pub today_column: Style,
+}
+
+impl Theme {
+    /// Rewrites the theme's colors to fit within `depth`.
+    fn with_depth(&self, depth: ColorDepth) -> Theme {
+        Theme {
+            header: self.header.with_depth(depth),
+            weekday_header: self.weekday_header.with_depth(depth),
+            weekend: self.weekend.with_depth(depth),
+            today: self.today.with_depth(depth),
+            highlight: self.highlight.with_depth(depth),
+            holiday: self.holiday.with_depth(depth),
+            current_week: self.current_week.with_depth(depth),
+            adjacent_day: self.adjacent_day.with_depth(depth),
+            today_column: self.today_column.with_depth(depth),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        ThemeName::Default.into()
+    }
+}
+
+impl From<ThemeName> for Theme {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Theme {
+                header: Style::none(),
+                weekday_header: Style::none(),
+                weekend: Style::none(),
+                today: Style::sgr("7", "27"),
+                highlight: Style::sgr("4", "24"),
+                holiday: Style::none(),
+                current_week: Style::sgr("100", "49"),
+                adjacent_day: Style::sgr("2", "22"),
+                today_column: Style::sgr("48;5;236", "49"),
+            },
+            ThemeName::Solarized => Theme {
+                header: Style::sgr("1;33", "22;39"),
+                weekday_header: Style::sgr("36", "39"),
+                weekend: Style::sgr("34", "39"),
+                today: Style::sgr("7;33", "27;39"),
+                highlight: Style::sgr("4;35", "24;39"),
+                holiday: Style::sgr("31", "39"),
+                current_week: Style::sgr("48;5;235", "49"),
+                adjacent_day: Style::sgr("2", "22"),
+                today_column: Style::sgr("48;5;236", "49"),
+            },
+            ThemeName::Mono => Theme {
+                header: Style::sgr("1", "22"),
+                weekday_header: Style::sgr("2", "22"),
+                weekend: Style::sgr("2", "22"),
+                today: Style::sgr("7", "27"),
+                highlight: Style::sgr("4", "24"),
+                holiday: Style::sgr("1", "22"),
+                current_week: Style::sgr("100", "49"),
+                adjacent_day: Style::sgr("2", "22"),
+                today_column: Style::sgr("48;5;236", "49"),
+            },
+            ThemeName::HighContrast => Theme {
+                header: Style::sgr("1;97", "22;39"),
+                weekday_header: Style::sgr("1", "22"),
+                weekend: Style::sgr("93", "39"),
+                today: Style::sgr("1;7", "22;27"),
+                highlight: Style::sgr("1;4", "22;24"),
+                holiday: Style::sgr("1;91", "22;39"),
+                current_week: Style::sgr("100", "49"),
+                adjacent_day: Style::sgr("90", "39"),
+                today_column: Style::sgr("48;5;236", "49"),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq)]
+pub enum FirstDayOfWeek {
+    Sunday,
+    Monday,
+}
+
+fn parse_week_start_offset(s: &str) -> Result<FirstDayOfWeek, String> {
+    match s.parse::<u8>() {
+        Ok(0) => Ok(FirstDayOfWeek::Monday),
+        Ok(6) => Ok(FirstDayOfWeek::Sunday),
+        Ok(1..=5) => Err(format!(
+            "Unsupported week start offset (only 0 for Monday and 6 for Sunday are supported): {}",
+            s
+        )),
+        _ => Err(format!(
+            "Invalid week start offset (expected 0 - 6, counting from Monday): {}",
+            s
+        )),
+    }
+}
+
+impl From<FirstDayOfWeek> for chrono::Weekday {
+    fn from(day: FirstDayOfWeek) -> Self {
+        match day {
+            FirstDayOfWeek::Sunday => chrono::Weekday::Sun,
+            FirstDayOfWeek::Monday => chrono::Weekday::Mon,
+        }
+    }
+}
+
+impl std::str::FromStr for FirstDayOfWeek {
+    type Err = String;
+
+    /// Parses a weekday name or abbreviation (e.g. `monday`, `Mon`, or `mo`), ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sunday" | "sun" | "su" => Ok(FirstDayOfWeek::Sunday),
+            "monday" | "mon" | "mo" => Ok(FirstDayOfWeek::Monday),
+            _ => Err(format!(
+                "Invalid first day of week: {} (expected sunday or monday)",
+                s
+            )),
+        }
+    }
+}
+
+/// The nth occurrence of a weekday within a month, e.g. the second Tuesday.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NthWeekday {
+    pub n: u8,
+    pub weekday: Weekday,
+}
+
+impl NthWeekday {
+    /// Returns `true` if `date` is the nth occurrence of the weekday within its month.
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        nth_weekday_of_month(date.year(), date.month(), self.weekday, self.n) == Some(date)
+    }
+}
+
+/// Returns the nth occurrence of `weekday` in the given month, or `None` if the month does not
+/// have that many (e.g. a fifth Friday).
+pub fn nth_weekday_of_month(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, String> {
+    s.parse::<Weekday>()
+        .map_err(|_| format!("Invalid weekday: {}", s))
+}
+
+fn parse_nth_weekday(s: &str) -> Result<NthWeekday, String> {
+    let (n, weekday) = s.split_once(':').ok_or_else(|| {
+        format!(
+            "Invalid nth weekday (expected N:WEEKDAY, e.g. 2:tue): {}",
+            s
+        )
+    })?;
+
+    let n = match n.parse::<u8>() {
+        Ok(n @ 1..=5) => n,
+        _ => return Err(format!("Invalid occurrence (must be 1 - 5): {}", n)),
+    };
+    let weekday = parse_weekday(weekday)?;
+
+    Ok(NthWeekday { n, weekday })
+}
+
+/// A built-in set of holidays, resolved for any year.
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HolidaySet {
+    /// United States federal holidays.
+    UsFederal,
+    /// Bank holidays in England and Wales.
+    UkBank,
+}
+
+/// When a holiday falls each year.
+#[derive(Clone, Copy, Debug)]
+enum HolidayDate {
+    /// The same month and day every year.
+    Fixed(u32, u32),
+    /// The nth occurrence of a weekday in a month, e.g. the fourth Thursday of November.
+    Nth(u32, Weekday, u8),
+    /// The last occurrence of a weekday in a month.
+    Last(u32, Weekday),
+    /// A number of days from Easter Sunday.
+    Easter(i64),
+}
+
+/// Where a holiday falling on a weekend is observed instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Observance {
+    /// On the day itself.
+    Actual,
+    /// On Friday for a Saturday, or Monday for a Sunday (US federal).
+    NearestWeekday,
+    /// On the next weekday that isn't already a holiday (UK substitute days).
+    NextWeekday,
+}
+
+#[derive(Debug)]
+struct HolidayRule {
+    name: &'static str,
+    date: HolidayDate,
+    observance: Observance,
+    /// The first year the holiday was observed.
+    since: i32,
+}
+
+const US_FEDERAL_HOLIDAYS: &[HolidayRule] = &[
+    HolidayRule {
+        name: "New Year's Day",
+        date: HolidayDate::Fixed(1, 1),
+        observance: Observance::NearestWeekday,
+        since: 1870,
+    },
+    HolidayRule {
+        name: "Martin Luther King Jr. Day",
+        date: HolidayDate::Nth(1, Weekday::Mon, 3),
+        observance: Observance::Actual,
+        since: 1986,
+    },
+    HolidayRule {
+        name: "Washington's Birthday",
+        date: HolidayDate::Nth(2, Weekday::Mon, 3),
+        observance: Observance::Actual,
+        since: 1971,
+    },
+    HolidayRule {
+        name: "Memorial Day",
+        date: HolidayDate::Last(5, Weekday::Mon),
+        observance: Observance::Actual,
+        since: 1971,
+    },
+    HolidayRule {
+        name: "Juneteenth",
+        date: HolidayDate::Fixed(6, 19),
+        observance: Observance::NearestWeekday,
+        since: 2021,
+    },
+    HolidayRule {
+        name: "Independence Day",
+        date: HolidayDate::Fixed(7, 4),
+        observance: Observance::NearestWeekday,
+        since: 1870,
+    },
+    HolidayRule {
+        name: "Labor Day",
+        date: HolidayDate::Nth(9, Weekday::Mon, 1),
+        observance: Observance::Actual,
+        since: 1894,
+    },
+    HolidayRule {
+        name: "Columbus Day",
+        date: HolidayDate::Nth(10, Weekday::Mon, 2),
+        observance: Observance::Actual,
+        since: 1971,
+    },
+    HolidayRule {
+        name: "Veterans Day",
+        date: HolidayDate::Fixed(11, 11),
+        observance: Observance::NearestWeekday,
+        since: 1978,
+    },
+    HolidayRule {
+        name: "Thanksgiving Day",
+        date: HolidayDate::Nth(11, Weekday::Thu, 4),
+        observance: Observance::Actual,
+        since: 1942,
+    },
+    HolidayRule {
+        name: "Christmas Day",
+        date: HolidayDate::Fixed(12, 25),
+        observance: Observance::NearestWeekday,
+        since: 1870,
+    },
+];
+
+const UK_BANK_HOLIDAYS: &[HolidayRule] = &[
+    HolidayRule {
+        name: "New Year's Day",
+        date: HolidayDate::Fixed(1, 1),
+        observance: Observance::NextWeekday,
+        since: 1974,
+    },
+    HolidayRule {
+        name: "Good Friday",
+        date: HolidayDate::Easter(-2),
+        observance: Observance::Actual,
+        since: 1871,
+    },
+    HolidayRule {
+        name: "Easter Monday",
+        date: HolidayDate::Easter(1),
+        observance: Observance::Actual,
+        since: 1871,
+    },
+    HolidayRule {
+        name: "Early May bank holiday",
+        date: HolidayDate::Nth(5, Weekday::Mon, 1),
+        observance: Observance::Actual,
+        since: 1978,
+    },
+    HolidayRule {
+        name: "Spring bank holiday",
+        date: HolidayDate::Last(5, Weekday::Mon),
+        observance: Observance::Actual,
+        since: 1971,
+    },
+    HolidayRule {
+        name: "Summer bank holiday",
+        date: HolidayDate::Last(8, Weekday::Mon),
+        observance: Observance::Actual,
+        since: 1971,
+    },
+    HolidayRule {
+        name: "Christmas Day",
+        date: HolidayDate::Fixed(12, 25),
+        observance: Observance::NextWeekday,
+        since: 1871,
+    },
+    HolidayRule {
+        name: "Boxing Day",
+        date: HolidayDate::Fixed(12, 26),
+        observance: Observance::NextWeekday,
+        since: 1871,
+    },
+];
+
+impl HolidaySet {
+    fn rules(&self) -> &'static [HolidayRule] {
+        match self {
+            HolidaySet::UsFederal => US_FEDERAL_HOLIDAYS,
+            HolidaySet::UkBank => UK_BANK_HOLIDAYS,
+        }
+    }
+
+    /// Returns the days off for the set's holidays in `year`. Holidays falling on a weekend are
+    /// moved to the day they're observed (e.g. `Christmas Day (observed)`), which for a Saturday
+    /// New Year's Day can be in the previous year.
+    ///
+    /// ```
+    /// use cal::HolidaySet;
+    /// use chrono::NaiveDate;
+    ///
+    /// let holidays = HolidaySet::UsFederal.holidays(2024);
+    /// let thanksgiving = NaiveDate::from_ymd_opt(2024, 11, 28).unwrap();
+    ///
+    /// assert_eq!(holidays[&thanksgiving], "Thanksgiving Day");
+    /// ```
+    pub fn holidays(&self, year: i32) -> BTreeMap<NaiveDate, String> {
+        let rules = self
+            .rules()
+            .iter()
+            .filter(|rule| year >= rule.since)
+            .filter_map(|rule| Some((rule, rule.date.resolve(year)?)))
+            .collect_vec();
+        let mut holidays = BTreeMap::new();
+
+        // substitute days skip over the holidays that fall on weekdays anyway
+        for &(rule, date) in &rules {
+            if !is_weekend(date) || rule.observance == Observance::Actual {
+                holidays.insert(date, rule.name.to_string());
+            }
+        }
+
+        for &(rule, date) in &rules {
+            if !is_weekend(date) {
+                continue;
+            }
+
+            let observed = match rule.observance {
+                Observance::Actual => continue,
+                Observance::NearestWeekday if date.weekday() == Weekday::Sat => date.pred_opt(),
+                Observance::NearestWeekday => date.succ_opt(),
+                Observance::NextWeekday => date
+                    .iter_days()
+                    .find(|date| !is_weekend(*date) && !holidays.contains_key(date)),
+            };
+
+            if let Some(observed) = observed {
+                holidays.insert(observed, format!("{} (observed)", rule.name));
+            }
+        }
+
+        holidays
+    }
+}
+
+impl HolidayDate {
+    fn resolve(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            HolidayDate::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayDate::Nth(month, weekday, n) => nth_weekday_of_month(year, month, weekday, n),
+            HolidayDate::Last(month, weekday) => {
+                let last_day = last_day_of_month_for(NaiveDate::from_ymd_opt(year, month, 1)?);
+                let days_back = (last_day.weekday().num_days_from_monday() + 7
+                    - weekday.num_days_from_monday())
+                    % 7;
+
+                last_day.checked_sub_days(chrono::Days::new(u64::from(days_back)))
+            }
+            HolidayDate::Easter(offset) => {
+                easter_sunday(year)?.checked_add_signed(chrono::Duration::days(offset))
+            }
+        }
+    }
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Returns the date of (Western) Easter Sunday in `year`, using the anonymous Gregorian algorithm.
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Resolves `sets` for each year in `years`.
+fn resolve_holiday_sets(
+    sets: &[HolidaySet],
+    years: std::ops::RangeInclusive<i32>,
+) -> BTreeMap<NaiveDate, String> {
+    sets.iter()
+        .cartesian_product(years)
+        .flat_map(|(set, year)| set.holidays(year))
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DateInput {
+    Year(Year),
+    YearMonth(Year, u32),
+    YearQuarter(Year, Quarter),
+    /// A contiguous span of quarters within a single year, e.g. `2024Q1-Q3`.
+    YearQuarterRange(Year, Quarter, Quarter),
+    /// A quarter of the current (calendar or fiscal) year. The year is resolved against the
+    /// current date when rendering.
+    CurrentYearQuarter(YearStyle, Quarter),
+    /// An explicit span of days, from the first date through the second (inclusive).
+    Range(NaiveDate, NaiveDate),
+    /// A span of days given as `(month, day)` pairs, e.g. `06-01..08-31`. The year is resolved
+    /// against the current date when rendering; a span that ends before it starts (e.g.
+    /// `12-01..02-28`) runs into the following year.
+    CurrentYearRange((u32, u32), (u32, u32)),
+    /// A number of months before (negative) or after the current month, e.g. `+2`. The month is
+    /// resolved against the current date when rendering.
+    RelativeMonth(i32),
+    /// A quarter shifted by a number of quarters, e.g. `FY24Q2+1`. The shift is applied once the
+    /// quarter's year has been resolved.
+    QuarterOffset(Box<DateInput>, i32),
+}
+
+impl std::str::FromStr for DateInput {
+    type Err = String;
+
+    /// Parses the same formats accepted on the command line.
+    ///
+    /// ```
+    /// use cal::{DateInput, Quarter, Year, YearStyle};
+    ///
+    /// assert_eq!(
+    ///     "2024Q1".parse::<DateInput>(),
+    ///     Ok(DateInput::YearQuarter(
+    ///         Year {
+    ///             style: YearStyle::Calendar,
+    ///             year: 2024
+    ///         },
+    ///         Quarter::Q1
+    ///     ))
+    /// );
+    /// assert!("2024-13".parse::<DateInput>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_date_input(s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quarter {
+    Q1,
+    Q2,
+    Q3,
+    Q4,
+}
+
+impl Quarter {
+    /// Returns the quarter that `month` (1 - 12) falls in. Fiscal years start in
+    /// `fiscal_year_start`, which is ignored for calendar years.
+    pub fn from_month(month: u32, style: YearStyle, fiscal_year_start: u32) -> Quarter {
+        let year_start = match style {
+            YearStyle::Calendar => 1,
+            YearStyle::Fiscal => fiscal_year_start,
+        };
+
+        match (12 + month - year_start) % 12 / 3 {
+            0 => Quarter::Q1,
+            1 => Quarter::Q2,
+            2 => Quarter::Q3,
+            _ => Quarter::Q4,
+        }
+    }
+
+    /// The quarter's number, from 1 through 4.
+    pub fn number(&self) -> u32 {
+        self.months_into_year() / 3 + 1
+    }
+
+    /// The number of months between the start of the year and the start of this quarter.
+    fn months_into_year(&self) -> u32 {
+        match self {
+            Quarter::Q1 => 0,
+            Quarter::Q2 => 3,
+            Quarter::Q3 => 6,
+            Quarter::Q4 => 9,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Year {
+    pub style: YearStyle,
+    pub year: i32,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum YearStyle {
+    Calendar,
+    Fiscal,
+}
+
+#[tracing::instrument(level = "debug", ret)]
+fn parse_date_input(s: &str) -> Result<DateInput, String> {
+    // support quarters followed by an offset, e.g. FY24Q2+1 or 2024Q1-1 (but not 2024Q1-Q3)
+    if let Some(date) = parse_quarter_offset(s)? {
+        return Ok(date);
+    }
+
+    // support 2024-06-01..2024-08-31 and 06-01..08-31 (in the current year) formats
+    if let Some((start, end)) = s.split_once("..") {
+        return parse_day_range(start, end);
+    }
+
+    // default to calendar year style
+    let style = YearStyle::Calendar;
+
+    // support bare Q1, Q2, Q3, Q4 format
+    if let Some(date) = parse_bare_quarter(s, style) {
+        return Ok(date);
+    }
+
+    // support anything prefixed with FY
+    if let Some(fiscal_year_stripped) = s.to_uppercase().strip_prefix("FY") {
+        let style = YearStyle::Fiscal;
+        if let Ok(year) = fiscal_year_stripped.parse::<i32>() {
+            return Ok(DateInput::Year(Year { style, year }));
+        }
+
+        // support bare Q1, Q2, Q3, Q4 format
+        if let Some(date) = parse_bare_quarter(fiscal_year_stripped, style) {
+            return Ok(date);
+        }
+
+        // support FY2024-Q1 format
+        if fiscal_year_stripped.contains("-Q") {
+            if let Some(date) = parse_year_quarter(fiscal_year_stripped, "-Q", style)? {
+                return Ok(date);
+            }
+        }
+        // support FY2024Q1 format
+        if fiscal_year_stripped.contains('Q') {
+            if let Some(date) = parse_year_quarter(fiscal_year_stripped, "Q", style)? {
+                return Ok(date);
+            }
+        }
+    }
+
+    // support +2 and -1 formats, years are never signed
+    if let Some(digits) = s.strip_prefix(['+', '-']) {
+        if (1..=3).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit()) {
+            let offset = s
+                .parse::<i32>()
+                .expect("a sign and up to 3 digits is a valid i32");
+
+            return Ok(DateInput::RelativeMonth(offset));
+        }
+    }
+
+    if let Ok(year) = s.parse::<i32>() {
+        match s.len() {
+            // support 24 format
+            // support 2024 format
+            2 | 4 => {
+                return Ok(DateInput::Year(Year { style, year }));
+            }
+
+            // support 202401 format
+            6 => {
+                let (year, month) = s.split_at(4);
+
+                if let (Ok(year), Ok(month)) = (year.parse::<i32>(), month.parse::<u32>()) {
+                    if (1..=12).contains(&month) {
+                        return Ok(DateInput::YearMonth(Year { style, year }, month));
+                    }
+
+                    return Err(format!(
+                        "Invalid month detected (must be 1 - 12): {}",
+                        month
+                    ));
+                }
+            }
+
+            1 | 3 => {
+                return Err(format!(
+                    "Invalid year: {} ({}-digit years are not supported; use a 4-digit year or 2-digit shorthand)",
+                    s,
+                    s.len()
+                ));
+            }
+
+            5 | 7.. => {
+                return Err(format!(
+                    "Invalid date: {} (use a 4-digit year like 2024 or a year and month like 202403)",
+                    s
+                ));
+            }
+
+            // fall through to the error case below
+            _ => {}
+        }
+
+        return Err(format!("Invalid date format: {}", s));
+    }
+
+    // support 2024-Q1 format
+    if s.contains("-Q") {
+        if let Some(date) = parse_year_quarter(s, "-Q", style)? {
+            return Ok(date);
+        }
+    }
+    // support 2024Q1 format
+    if s.contains('Q') {
+        if let Some(date) = parse_year_quarter(s, "Q", style)? {
+            return Ok(date);
+        }
+    }
+
+    // support 2024/01 and 01/2024 formats
+    if let Some((left, right)) = s.split_once('/') {
+        let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+        if is_number(left) && is_number(right) {
+            let (year, month) = match (left.len(), right.len()) {
+                (4, 1..=2) => (left, right),
+                (1..=2, 4) => (right, left),
+                _ => {
+                    return Err(format!(
+                        "Ambiguous date format: {} (use YYYY-MM, e.g. 2024-03)",
+                        s
+                    ))
+                }
+            };
+
+            if let (Ok(year), Ok(month)) = (year.parse::<i32>(), month.parse::<u32>()) {
+                if (1..=12).contains(&month) {
+                    return Ok(DateInput::YearMonth(Year { style, year }, month));
+                }
+
+                return Err(format!(
+                    "Invalid month detected (must be 1 - 12): {}",
+                    month
+                ));
+            }
+        }
+    }
+
+    // support 2024-01 format
+    if let Some((year, month)) = s.split_once('-') {
+        if let (Ok(year), Ok(month)) = (year.parse::<i32>(), month.parse::<u32>()) {
+            if (1..=12).contains(&month) {
+                return Ok(DateInput::YearMonth(Year { style, year }, month));
+            }
+        }
+    }
+
+    Err(format!("Invalid date format: {}", s))
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn parse_timezone(s: &str) -> Result<Tz, String> {
+    s.parse::<Tz>().map_err(|_| {
+        format!(
+            "Unknown time zone: {} (expected an IANA name like America/New_York)",
+            s
+        )
+    })
+}
+
+/// Returns today's date in `timezone` (or the system's local time zone), unless it is overridden
+/// by a `CAL_TODAY` environment variable (YYYY-MM-DD). Every read of the current date goes through
+/// here, so the override makes the output reproducible.
+///
+/// All of the calendar math uses `NaiveDate`, so once today's date is known, DST and time zone
+/// transitions can't shift which day is displayed as today.
+pub fn today(timezone: Option<Tz>) -> NaiveDate {
+    if let Some(value) = std::env::var_os("CAL_TODAY") {
+        match value.to_str().map(parse_iso_date) {
+            Some(Ok(date)) => return date,
+            _ => tracing::warn!("Ignoring invalid CAL_TODAY: {:?}", value),
+        }
+    }
+
+    today_in(timezone, Utc::now())
+}
+
+fn today_in(timezone: Option<Tz>, now: DateTime<Utc>) -> NaiveDate {
+    match timezone {
+        Some(timezone) => now.with_timezone(&timezone).date_naive(),
+        None => now.with_timezone(&Local).date_naive(),
+    }
+}
+
+/// Parses a date like `parse_date_input`, but only in one of the canonical forms listed under
+/// `--strict`.
+#[tracing::instrument(level = "debug", ret)]
+fn parse_date_input_strict(s: &str) -> Result<DateInput, String> {
+    let date_input = parse_date_input(s)?;
+
+    let is_digits = |s: &str, len: usize| s.len() == len && s.chars().all(|c| c.is_ascii_digit());
+    let is_quarter = |s: &str| matches!(s, "Q1" | "Q2" | "Q3" | "Q4");
+    let is_year_quarter = |s: &str| {
+        s.split_once('-')
+            .is_some_and(|(year, quarter)| is_digits(year, 4) && is_quarter(quarter))
+    };
+    let is_canonical = match s.strip_prefix("FY") {
+        Some(fiscal) => is_digits(fiscal, 4) || is_quarter(fiscal) || is_year_quarter(fiscal),
+        None => {
+            let is_date = |s: &str, len: usize| {
+                s.len() == len && s.chars().all(|c| c.is_ascii_digit() || c == '-')
+            };
+
+            is_digits(s, 4)
+                || is_quarter(s)
+                || is_year_quarter(s)
+                || is_date(s, 7)
+                || matches!(date_input, DateInput::RelativeMonth(_))
+                || s.split_once("..").is_some_and(|(start, end)| {
+                    (is_date(start, 10) && is_date(end, 10))
+                        || (is_date(start, 5) && is_date(end, 5))
+                })
+        }
+    };
+
+    if is_canonical {
+        Ok(date_input)
+    } else {
+        Err(format!(
+            "Non-canonical date: {} (see `--strict` in --help for the accepted forms)",
+            s
+        ))
+    }
+}
+
+fn parse_day_range(start: &str, end: &str) -> Result<DateInput, String> {
+    if let (Ok(start), Ok(end)) = (parse_iso_date(start), parse_iso_date(end)) {
+        if end < start {
+            return Err(format!("Range ends before it starts: {}..{}", start, end));
+        }
+
+        return Ok(DateInput::Range(start, end));
+    }
+
+    // validate against a leap year so that 02-29 is accepted
+    let parse_month_day = |s: &str| {
+        NaiveDate::parse_from_str(&format!("2000-{}", s), "%Y-%m-%d")
+            .ok()
+            .filter(|_| s.len() == 5)
+            .map(|date| (date.month(), date.day()))
+    };
+
+    match (parse_month_day(start), parse_month_day(end)) {
+        (Some(start), Some(end)) => Ok(DateInput::CurrentYearRange(start, end)),
+        _ => Err(format!(
+            "Invalid range (expected YYYY-MM-DD..YYYY-MM-DD or MM-DD..MM-DD): {}..{}",
+            start, end
+        )),
+    }
+}
+
+/// Returns the dates for a `(month, day)` span starting in `year`. Spans that end before they
+/// start run into the following year, and Feb 29 falls back to Feb 28 outside of leap years. Fails
+/// when the span runs past the supported range of years.
+fn month_day_range(
+    year: i32,
+    start: (u32, u32),
+    end: (u32, u32),
+) -> Result<(NaiveDate, NaiveDate), CalError> {
+    let date = |year: i32, (month, day): (u32, u32)| {
+        // the month and day were validated when parsing, so only the year can be out of range
+        NaiveDate::from_ymd_opt(year, month, day)
+            .or_else(|| NaiveDate::from_ymd_opt(year, month, day - 1))
+            .ok_or_else(|| CalError::OutOfRange(format!("{}-{:02}-{:02}", year, month, day)))
+    };
+    let end_year = if end < start {
+        year.checked_add(1)
+            .ok_or_else(|| CalError::OutOfRange(format!("{}-{:02}-{:02}", year, end.0, end.1)))?
+    } else {
+        year
+    };
+
+    Ok((date(year, start)?, date(end_year, end)?))
+}
+
+fn parse_iso_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date (expected YYYY-MM-DD): {}", s))
+}
+
+fn parse_weekday_of(s: &str) -> Result<NaiveDate, String> {
+    if let Ok(date) = parse_iso_date(s) {
+        return Ok(date);
+    }
+
+    match parse_date_input(s) {
+        Ok(DateInput::YearMonth(year, month)) => NaiveDate::from_ymd_opt(year.year, month, 1)
+            .ok_or_else(|| format!("Invalid date: {}", s)),
+        _ => Err(format!(
+            "Invalid date (expected YYYY-MM-DD or YYYY-MM): {}",
+            s
+        )),
+    }
+}
+
+fn parse_month_input(s: &str) -> Result<DateInput, String> {
+    match parse_date_input(s)? {
+        date_input @ DateInput::YearMonth(..) => Ok(date_input),
+        _ => Err(format!(
+            "Expected a specific month (e.g. 2024-03), got: {}",
+            s
+        )),
+    }
+}
+
+fn parse_quarter_offset(s: &str) -> Result<Option<DateInput>, String> {
+    let Some(index) = s.rfind(['+', '-']) else {
+        return Ok(None);
+    };
+    let (quarter, offset) = s.split_at(index);
+    let digits = &offset[1..];
+
+    if !quarter.to_uppercase().contains('Q')
+        || !(1..=3).contains(&digits.len())
+        || !digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return Ok(None);
+    }
+
+    let offset = offset
+        .parse::<i32>()
+        .expect("a sign and up to 3 digits is a valid i32");
+
+    match parse_date_input(quarter)? {
+        date_input @ (DateInput::YearQuarter(..) | DateInput::CurrentYearQuarter(..)) => {
+            Ok(Some(DateInput::QuarterOffset(Box::new(date_input), offset)))
+        }
+        _ => Err(format!(
+            "Only a single quarter can be offset (e.g. 2024Q1+1), got: {}",
+            s
+        )),
+    }
+}
+
+fn parse_year_quarter(
+    s: &str,
+    delimiter: &str,
+    style: YearStyle,
+) -> Result<Option<DateInput>, String> {
+    let parse_quarter = |quarter: &str| match quarter {
+        "1" => Some(Quarter::Q1),
+        "2" => Some(Quarter::Q2),
+        "3" => Some(Quarter::Q3),
+        "4" => Some(Quarter::Q4),
+        _ => None,
+    };
+
+    if let Some((year, quarter)) = s.split_once(delimiter) {
+        let Ok(year) = year.parse::<i32>() else {
+            return Ok(None);
+        };
+
+        // support 2024Q1-Q3 format
+        if let Some((start, end)) = quarter.split_once("-Q") {
+            if let (Some(start), Some(end)) = (parse_quarter(start), parse_quarter(end)) {
+                if end < start {
+                    return Err(format!(
+                        "Invalid quarter range (end is before start): {}",
+                        s
+                    ));
+                }
+
+                return Ok(Some(DateInput::YearQuarterRange(
+                    Year { style, year },
+                    start,
+                    end,
+                )));
+            }
+        }
+
+        if let Some(quarter) = parse_quarter(quarter) {
+            return Ok(Some(DateInput::YearQuarter(Year { style, year }, quarter)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Expands a two-digit year into the current century. With a `year_pivot`, two-digit years at or
+/// above the pivot are placed in the previous century instead.
+fn normalize_short_year(current_date: NaiveDate, year_pivot: Option<u32>, year: i32) -> i32 {
+    match year {
+        0..=99 => {
+            let current_year = current_date.year();
+            let current_century = match year_pivot {
+                Some(pivot) if year >= pivot as i32 => current_year / 100 - 1,
+                _ => current_year / 100,
+            };
+
+            current_century * 100 + year
+        }
+        _ => year,
+    }
+}
+
+fn parse_bare_quarter(s: &str, style: YearStyle) -> Option<DateInput> {
+    if let Some(quarter) = match s.to_uppercase().as_str() {
+        "Q1" => Some(Quarter::Q1),
+        "Q2" => Some(Quarter::Q2),
+        "Q3" => Some(Quarter::Q3),
+        "Q4" => Some(Quarter::Q4),
+        _ => None,
+    } {
+        return Some(DateInput::CurrentYearQuarter(style, quarter));
+    }
+
+    None
+}
+
+fn determine_current_year(
+    current_date: NaiveDate,
+    style: YearStyle,
+    fiscal_year_start: u32,
+) -> i32 {
+    let current_year = current_date.year();
+
+    match style {
+        YearStyle::Calendar => current_year,
+        YearStyle::Fiscal => {
+            // fiscal years are named after the calendar year they end in
+            if fiscal_year_start > 1 && current_date.month() >= fiscal_year_start {
+                current_year + 1
+            } else {
+                current_year
+            }
+        }
+    }
+}
+
+/// Returns the first day of the fiscal year `year`, which begins in the previous calendar year
+/// unless `fiscal_year_start` is January.
+fn fiscal_year_start_date(year: i32, fiscal_year_start: u32) -> Result<NaiveDate, CalError> {
+    if fiscal_year_start == 1 {
+        month_start(year, 1)
+    } else {
+        let previous_year = year
+            .checked_sub(1)
+            .ok_or_else(|| CalError::OutOfRange(year.to_string()))?;
+
+        month_start(previous_year, fiscal_year_start)
+    }
+}
+
+/// Returns the 1st of the given month, or an error naming it (e.g. `262143-12`) when it can't be
+/// represented.
+fn month_start(year: i32, month: u32) -> Result<NaiveDate, CalError> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| CalError::OutOfRange(format!("{}-{:02}", year, month)))
+}
+
+#[cfg(target_os = "macos")]
+fn get_system_default_first_workday() -> Option<Weekday> {
+    use plist::Value;
+
+    let plist_path = match home::home_dir() {
+        Some(mut path) => {
+            path.push("Library/Preferences/.GlobalPreferences.plist");
+            path
+        }
+        None => return None,
+    };
+
+    let plist = Value::from_file(plist_path).ok()?;
+
+    if let Some(dict) = plist.as_dictionary() {
+        if let Some(Value::Dictionary(calendars)) = dict.get("AppleFirstWeekday") {
+            if let Some(Value::Integer(first_weekday)) = calendars.get("gregorian") {
+                return match first_weekday.as_signed()? {
+                    1 => Some(Weekday::Sun),
+                    2 => Some(Weekday::Mon),
+                    _ => None,
+                };
+            }
+        }
+    } else {
+        // could not process the plist file as a dictionary, we shouldn't consider this a
+        // "succesful" read (which would default back to Sunday).
+        return None;
+    }
+
+    // On macOS the default is Sunday if not set via system preferences. When it is in its default
+    // value, the plist file will not contain the key `AppleFirstWeekday`.
+    Some(Weekday::Sun)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn get_system_default_first_workday() -> Option<Weekday> {
+    None
+}
+
+/// Where the effective first day of the week came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FirstDayOfWeekSource {
+    /// Set explicitly via `--first-day-of-week` (or `--week-start-offset`).
+    Argument,
+    /// Read from the operating system's preferences.
+    SystemPreference,
+    /// Nothing was specified or detected, so Monday was used.
+    Fallback,
+}
+
+fn determine_default_first_day_of_week(
+    first_day_of_week: Option<FirstDayOfWeek>,
+) -> (chrono::Weekday, FirstDayOfWeekSource) {
+    if let Some(first_day_of_week) = first_day_of_week {
+        (first_day_of_week.into(), FirstDayOfWeekSource::Argument)
+    } else {
+        if let Some(weekday) = get_system_default_first_workday() {
+            return (weekday, FirstDayOfWeekSource::SystemPreference);
+        }
+
+        (Weekday::Mon, FirstDayOfWeekSource::Fallback)
+    }
+}
+
+/// Options controlling how months are rendered.
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    pub color: ColorWhen,
+    /// The number of colors the terminal can show.
+    pub color_depth: ColorDepth,
+    /// The date to highlight as "today".
+    pub current_date: NaiveDate,
+    pub show_year_only_once: bool,
+    pub show_day_count: bool,
+    /// Note the ISO week-year in headers of months that have days in another ISO year's weeks.
+    pub show_iso_week_year: bool,
+    pub header_format: HeaderFormat,
+    /// The rule drawn under the weekday header, if any.
+    pub header_rule: Option<HeaderRule>,
+    pub highlight_nth_weekdays: Vec<NthWeekday>,
+    pub highlight_days_of_month: Vec<u32>,
+    /// The number of months to display per row.
+    pub columns: usize,
+    /// The number of week rows to display for every month, instead of as many as needed.
+    pub force_weeks: Option<usize>,
+    /// Size each row of months to its own longest month, rather than the longest month overall.
+    pub trim_empty_rows: bool,
+    pub quarter_dividers: bool,
+    /// Display each quarter as its own row with a section header, instead of `columns` months per
+    /// row.
+    pub group_by_quarter: bool,
+    /// Labels quarters by the fiscal year starting in this month, instead of the calendar year.
+    pub fiscal_year_start: Option<u32>,
+    /// Print the weekday header once at the top when the months are stacked in a single column.
+    pub shared_weekday_header: bool,
+    /// Highlights the header of the month containing this date.
+    pub highlight_month: Option<NaiveDate>,
+    /// Bracket today's date when color is not being used.
+    pub ascii_today_marker: bool,
+    /// Replace each Monday's date with its ISO week number (e.g. `W10`).
+    pub weeknum_in_cells: bool,
+    /// Overrides the theme's style for today.
+    pub highlight_style: Option<HighlightStyle>,
+    /// Mark the week containing today.
+    pub highlight_current_week: bool,
+    /// Shade the column for today's weekday.
+    pub anchor_today_column: bool,
+    /// Render everything in reverse video, flipping today's highlight back to normal video.
+    pub invert: bool,
+    /// How days before today are styled.
+    pub past_style: PastStyle,
+    pub day_pad: DayPad,
+    pub cell_content: CellContent,
+    /// Show weekend days as an emoji instead of their number.
+    pub emoji_weekends: bool,
+    pub day_gap: DayGap,
+    /// Fills the empty cells within a month, instead of leaving them blank.
+    pub fill_empty: Option<char>,
+    /// Fills the empty cells within a month with the neighboring months' days.
+    pub adjacent_days: bool,
+    /// Holiday names by date.
+    pub holidays: BTreeMap<NaiveDate, String>,
+    /// Dates from `--highlight-file`.
+    pub highlighted_dates: BTreeMap<NaiveDate, HighlightedDate>,
+    /// Labels from `--overlay-notes`, printed beside their month.
+    pub notes: BTreeMap<NaiveDate, String>,
+    /// Show Saturday and Sunday in a single column.
+    pub fold_weekends: bool,
+    /// Separate months in a row with a tab instead of two spaces.
+    pub use_tabs: bool,
+    pub theme: Theme,
+}
+
+impl RenderOptions {
+    /// Adds `holidays`, keeping the existing name for any date that already has one.
+    fn add_holidays(&mut self, holidays: BTreeMap<NaiveDate, String>) {
+        for (date, name) in holidays {
+            self.holidays.entry(date).or_insert(name);
+        }
+    }
+
+    fn is_highlighted(&self, date: NaiveDate) -> bool {
+        self.highlight_nth_weekdays
+            .iter()
+            .any(|nth_weekday| nth_weekday.matches(date))
+            || self.is_highlighted_day_of_month(date)
+            || self.highlighted_dates.contains_key(&date)
+    }
+
+    /// Returns the style for a highlighted `date`, which may have its own color. `--highlight-style`
+    /// takes precedence over both.
+    fn highlight_style_for(&self, date: NaiveDate) -> Style {
+        if let Some(style) = self.marker_style() {
+            return style;
+        }
+
+        match self
+            .highlighted_dates
+            .get(&date)
+            .and_then(|date| date.color)
+        {
+            Some(color) => Style {
+                on: color.foreground(self.color_depth),
+                off: "39".to_string(),
+            },
+            None => self.theme.highlight.clone(),
+        }
+    }
+
+    fn is_highlighted_day_of_month(&self, date: NaiveDate) -> bool {
+        self.highlight_days_of_month.contains(&date.day())
+    }
+
+    /// Labels the quarter containing `date`, e.g. `Q2 2024` (or `Q4 FY2024` for fiscal years).
+    fn quarter_label(&self, date: NaiveDate) -> String {
+        let (style, fiscal_year_start) = match self.fiscal_year_start {
+            Some(fiscal_year_start) => (YearStyle::Fiscal, fiscal_year_start),
+            None => (YearStyle::Calendar, 1),
+        };
+        let year = determine_current_year(date, style, fiscal_year_start);
+        let quarter = Quarter::from_month(date.month(), style, fiscal_year_start).number();
+
+        match style {
+            YearStyle::Calendar => format!("Q{} {}", quarter, year),
+            YearStyle::Fiscal => format!("Q{} FY{}", quarter, year),
+        }
+    }
+
+    /// Returns `true` when dates may be marked with plain text characters, in which case every
+    /// row gets a leading and trailing gutter so that markers have room on both sides of a day.
+    fn uses_gutters(&self) -> bool {
+        self.highlight_style == Some(HighlightStyle::Box)
+            || self.weeknum_in_cells
+            || (!show_color(self.color)
+                && (self.ascii_today_marker
+                    || self.highlight_current_week
+                    || self.adjacent_days
+                    || !self.highlighted_dates.is_empty()
+                    || !self.highlight_days_of_month.is_empty()))
+    }
+
+    /// Returns `true` if `week` is the week containing today. Weeks are split at month boundaries,
+    /// so this can be true for the last row of one month and the first row of the next.
+    fn is_current_week(&self, week: &Week, first_day_of_week: Weekday) -> bool {
+        let current_week = week_index(self.current_date, first_day_of_week);
+
+        week.days(first_day_of_week)
+            .any(|day| week_index(day, first_day_of_week) == current_week)
+    }
+
+    /// Returns the style used to mark today when color is enabled.
+    fn today_style(&self) -> Style {
+        self.marker_style()
+            .unwrap_or_else(|| self.theme.today.clone())
+    }
+
+    /// Returns the style chosen with `--highlight-style`, shared by today and highlighted dates.
+    fn marker_style(&self) -> Option<Style> {
+        self.highlight_style.map(|style| match style {
+            HighlightStyle::Reverse => Style::sgr("7", "27"),
+            HighlightStyle::Underline => Style::sgr("4", "24"),
+            HighlightStyle::Bold => Style::sgr("1", "22"),
+            // the brackets are drawn in the gutters instead
+            HighlightStyle::Box => Style::none(),
+        })
+    }
+
+    /// Returns `true` if `weekday`'s column is shaded by `--anchor-today-column`.
+    fn is_today_column(&self, weekday: Weekday) -> bool {
+        self.anchor_today_column && show_color(self.color) && weekday == self.current_date.weekday()
+    }
+
+    /// Returns the style layered over the days before today.
+    fn past_style(&self) -> Style {
+        match self.past_style {
+            // ANSI codes for dim on and off
+            PastStyle::Dim => Style::sgr("2", "22"),
+            // ANSI codes for strikethrough on and off
+            PastStyle::Strike => Style::sgr("9", "29"),
+            PastStyle::None => Style::none(),
+        }
+    }
+
+    /// Returns the characters placed in the gutters on either side of `date`, if it is marked.
+    fn plain_marker(&self, date: NaiveDate) -> Option<(char, char)> {
+        if !self.uses_gutters() {
+            return None;
+        }
+
+        let is_boxed = self.highlight_style == Some(HighlightStyle::Box);
+        let marks_today = date == self.current_date && (self.ascii_today_marker || is_boxed);
+        let marks_day_of_month = (!show_color(self.color) || is_boxed)
+            && (self.is_highlighted_day_of_month(date)
+                || self.highlighted_dates.contains_key(&date));
+
+        if marks_today || marks_day_of_month {
+            Some(('[', ']'))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the text placed between day cells (and the weekday names in the header).
+    fn day_gap(&self) -> &'static str {
+        match self.day_gap {
+            DayGap::Normal => " ",
+            // gutters hold the plain text markers, which take up a whole column and so can't
+            // replace a thin space (or no space at all) without shifting the rest of the row
+            DayGap::Thin | DayGap::None if self.uses_gutters() => " ",
+            DayGap::Thin => "\u{2009}",
+            DayGap::None => "",
+        }
+    }
+
+    /// Returns the text placed between months that are side by side.
+    fn month_separator(&self) -> &'static str {
+        if self.use_tabs {
+            "\t"
+        } else {
+            "  "
+        }
+    }
+
+    /// Returns the width of a single day's cell.
+    fn cell_width(&self) -> usize {
+        match self.cell_content {
+            CellContent::Day | CellContent::WeekdayIndex => 2,
+            CellContent::DayOfYear => 3,
+        }
+    }
+
+    /// Returns the width of a single month's columns.
+    fn month_width(&self) -> usize {
+        let gap = self.day_gap().chars().count();
+        let width = if self.fold_weekends {
+            // the weekend column holds two cells joined by a `/`
+            7 * self.cell_width() + 1 + 5 * gap
+        } else {
+            7 * self.cell_width() + 6 * gap
+        };
+
+        if self.uses_gutters() {
+            width + 2
+        } else {
+            width
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            color: ColorWhen::Auto,
+            color_depth: ColorDepth::Palette,
+            current_date: today(None),
+            show_year_only_once: false,
+            show_day_count: false,
+            show_iso_week_year: false,
+            header_format: HeaderFormat::Name,
+            header_rule: None,
+            highlight_nth_weekdays: vec![],
+            highlight_days_of_month: vec![],
+            columns: 3,
+            force_weeks: None,
+            trim_empty_rows: false,
+            quarter_dividers: false,
+            group_by_quarter: false,
+            fiscal_year_start: None,
+            shared_weekday_header: false,
+            highlight_month: None,
+            ascii_today_marker: false,
+            weeknum_in_cells: false,
+            highlight_style: None,
+            highlight_current_week: false,
+            anchor_today_column: false,
+            invert: false,
+            past_style: PastStyle::None,
+            day_pad: DayPad::Space,
+            cell_content: CellContent::Day,
+            emoji_weekends: false,
+            day_gap: DayGap::Normal,
+            fill_empty: None,
+            adjacent_days: false,
+            holidays: BTreeMap::new(),
+            highlighted_dates: BTreeMap::new(),
+            notes: BTreeMap::new(),
+            fold_weekends: false,
+            use_tabs: false,
+            theme: Theme::default(),
+        }
+    }
+}
+
+/// Counts of the days displayed in a `MonthRange`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RangeSummary {
+    pub days: usize,
+    pub weekend_days: usize,
+    /// The number of holidays and business days displayed, or `None` when no holidays are loaded.
+    pub holidays: Option<HolidaySummary>,
+}
+
+/// Counts of the holidays in a `MonthRange`, see [`RangeSummary`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HolidaySummary {
+    pub holidays: usize,
+    /// Days that are neither on a weekend nor a holiday.
+    pub business_days: usize,
+}
+
+impl fmt::Display for RangeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} days, {} weekend days", self.days, self.weekend_days)?;
+
+        if let Some(holidays) = self.holidays {
+            write!(
+                f,
+                ", {} holidays, {} business days",
+                holidays.holidays, holidays.business_days
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Layout of the SVG output, in user units. Each month is a grid of 7 columns and 8 rows (the month
+// header, the weekday header, and up to 6 weeks).
+const SVG_CELL_WIDTH: usize = 28;
+const SVG_CELL_HEIGHT: usize = 20;
+const SVG_MONTH_ROWS: usize = 8;
+const SVG_MONTH_GAP: usize = 20;
+const SVG_MONTH_WIDTH: usize = 7 * SVG_CELL_WIDTH;
+const SVG_MONTH_HEIGHT: usize = SVG_MONTH_ROWS * SVG_CELL_HEIGHT;
+
+#[derive(Debug)]
+pub struct MonthRange {
+    pub months: Vec<Month>,
+}
+
+impl MonthRange {
+    /// Builds the months covering `start_date` through `end_date` (inclusive). The first and last
+    /// months only include the days within the range.
+    ///
+    /// ```
+    /// use cal::MonthRange;
+    /// use chrono::{NaiveDate, Weekday};
+    ///
+    /// let start_date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+    /// let end_date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+    /// let month_range = MonthRange::new(start_date, end_date, Weekday::Mon);
+    ///
+    /// assert_eq!(month_range.months.len(), 3);
+    /// assert_eq!(month_range.date_range(), (start_date, end_date));
+    /// ```
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate, first_day_of_week: Weekday) -> Self {
+        build_month_range(start_date, end_date, first_day_of_week)
+    }
+
+    /// Builds the twelve months of the calendar year `year`, or `None` if the year can't be
+    /// represented.
+    ///
+    /// ```
+    /// use cal::MonthRange;
+    /// use chrono::Weekday;
+    ///
+    /// let month_range = MonthRange::from_year(2024, Weekday::Sun).unwrap();
+    ///
+    /// assert_eq!(month_range.months.len(), 12);
+    /// assert_eq!(month_range.summary(&Default::default()).days, 366);
+    /// assert!(MonthRange::from_year(300_000, Weekday::Sun).is_none());
+    /// ```
+    pub fn from_year(year: i32, first_day_of_week: Weekday) -> Option<Self> {
+        let start_date = NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let end_date = NaiveDate::from_ymd_opt(year, 12, 31)?;
+
+        Some(build_month_range(start_date, end_date, first_day_of_week))
+    }
+
+    /// Builds the single month `month` (1 - 12) of `year`, or `None` if it isn't a valid month.
+    ///
+    /// ```
+    /// use cal::MonthRange;
+    /// use chrono::{NaiveDate, Weekday};
+    ///
+    /// let month_range = MonthRange::from_month(2024, 2, Weekday::Mon).unwrap();
+    ///
+    /// assert_eq!(
+    ///     month_range.date_range(),
+    ///     (
+    ///         NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+    ///         NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+    ///     )
+    /// );
+    /// assert!(MonthRange::from_month(2024, 13, Weekday::Mon).is_none());
+    /// ```
+    pub fn from_month(year: i32, month: u32, first_day_of_week: Weekday) -> Option<Self> {
+        let start_date = NaiveDate::from_ymd_opt(year, month, 1)?;
+
+        Some(build_month_range(
+            start_date,
+            last_day_of_month_for(start_date),
+            first_day_of_week,
+        ))
+    }
+
+    /// Returns `true` if `date` is one of the days displayed in this range.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.months.iter().any(|month| month.contains(date))
+    }
+
+    /// Returns an iterator over every day displayed in this range, in chronological order.
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.months.iter().flat_map(|month| month.days())
+    }
+
+    /// Counts the days displayed in this range. The holidays and business days are only counted
+    /// when `holidays` isn't empty.
+    pub fn summary(&self, holidays: &BTreeMap<NaiveDate, String>) -> RangeSummary {
+        let (days, weekend_days, holiday_days, weekday_holidays) = self.days().fold(
+            (0, 0, 0, 0),
+            |(days, weekend_days, holiday_days, weekday_holidays), date| {
+                let is_weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+                let is_holiday = holidays.contains_key(&date);
+
+                (
+                    days + 1,
+                    weekend_days + usize::from(is_weekend),
+                    holiday_days + usize::from(is_holiday),
+                    weekday_holidays + usize::from(is_holiday && !is_weekend),
+                )
+            },
+        );
+
+        RangeSummary {
+            days,
+            weekend_days,
+            holidays: (!holidays.is_empty()).then_some(HolidaySummary {
+                holidays: holiday_days,
+                business_days: days - weekend_days - weekday_holidays,
+            }),
+        }
+    }
+
+    /// Returns the first and last day displayed in this range.
+    ///
+    /// Panics if the range does not contain any months.
+    pub fn date_range(&self) -> (NaiveDate, NaiveDate) {
+        let first = self.months.first().expect("no months in range");
+        let last = self.months.last().expect("no months in range");
+
+        (first.start_date, last.end_date())
+    }
+
+    #[tracing::instrument]
+    fn print(&self, options: &RenderOptions) -> String {
+        let mut output = String::new();
+
+        let shared_year = if options.show_year_only_once && self.months.len() > 1 {
+            self.months
+                .iter()
+                .map(|month| month.start_date.year())
+                .all_equal_value()
+                .ok()
+        } else {
+            None
+        };
+
+        let rows: Vec<&[Month]> = if options.group_by_quarter {
+            self.months
+                .chunk_by(|a, b| {
+                    options.quarter_label(a.start_date) == options.quarter_label(b.start_date)
+                })
+                .collect()
+        } else {
+            self.months.chunks(options.columns).collect()
+        };
+
+        let row_width = |months: usize| {
+            let month_width = options.month_width();
+
+            months * month_width + months.saturating_sub(1) * 2
+        };
+        let month_width = options.month_width();
+        let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let width = row_width(columns);
+
+        if let Some(year) = shared_year {
+            output.push_str(&pad_to_width(
+                &year.to_string(),
+                width,
+                fmt::Alignment::Center,
+            ));
+            output.push('\n');
+        }
+
+        // a single header only works when every month is stacked and starts the week on the same day
+        let shared_weekday_header = options.shared_weekday_header
+            && columns == 1
+            && self
+                .months
+                .iter()
+                .map(|month| month.first_day_of_week)
+                .all_equal();
+
+        if shared_weekday_header {
+            self.months[0].print_weekday_header(options, &mut output);
+            output.push('\n');
+
+            if let Some(rule) = options.header_rule {
+                self.months[0].print_header_rule(rule, options, &mut output);
+                output.push('\n');
+            }
+        }
+
+        for (chunk_index, chunk) in rows.into_iter().enumerate() {
+            if chunk_index > 0 {
+                // quarters start in January, April, July, and October (or every row, when grouped)
+                let starts_quarter =
+                    options.group_by_quarter || chunk[0].start_date.month() % 3 == 1;
+
+                if options.quarter_dividers && starts_quarter {
+                    let divider = "─".repeat(width);
+
+                    if show_color(options.color) {
+                        // ANSI codes for dim on and off
+                        output.push_str(&format!("\x1B[2m{}\x1B[22m", divider));
+                    } else {
+                        output.push_str(&divider);
+                    }
+                }
+                output.push('\n');
+            }
+
+            if options.group_by_quarter {
+                let label = options.quarter_label(chunk[0].start_date);
+                let padded = pad_to_width(&label, row_width(chunk.len()), fmt::Alignment::Center);
+
+                if show_color(options.color) {
+                    output.push_str(&padded.replacen(
+                        &label,
+                        &options.theme.header.paint(&label),
+                        1,
+                    ));
+                } else {
+                    output.push_str(&padded);
+                }
+                output.push('\n');
+            }
+
+            let chunk_start = output.len();
+
+            // print the month headers
+            for (index, month) in chunk.iter().enumerate() {
+                if index > 0 {
+                    output.push_str(options.month_separator());
+                }
+
+                month.print_header(options, shared_year.is_none(), &mut output);
+            }
+            output.push('\n');
+
+            // print the weekday headers
+            if !shared_weekday_header {
+                for (index, month) in chunk.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(options.month_separator());
+                    }
+
+                    month.print_weekday_header(options, &mut output);
+                }
+                output.push('\n');
+
+                if let Some(rule) = options.header_rule {
+                    for (index, month) in chunk.iter().enumerate() {
+                        if index > 0 {
+                            output.push_str(options.month_separator());
+                        }
+
+                        month.print_header_rule(rule, options, &mut output);
+                    }
+                    output.push('\n');
+                }
+            }
+
+            let max_weeks = options.force_weeks.unwrap_or_else(|| {
+                let months = if options.trim_empty_rows {
+                    chunk
+                } else {
+                    &self.months
+                };
+
+                months
+                    .iter()
+                    .map(|month| month.weeks.len())
+                    .max()
+                    .unwrap_or(0)
+            });
+
+            for week_index in 0..max_weeks {
+                for (index, month) in chunk.iter().enumerate() {
+                    if index > 0 {
+                        output.push_str(options.month_separator());
+                    }
+
+                    let week = month.weeks.get(week_index);
+                    match week {
+                        Some(week) => week.print(options, month.first_day_of_week, &mut output),
+                        None => {
+                            output.push_str(&" ".repeat(month_width));
+                        }
+                    }
+                }
+                output.push('\n');
+            }
+
+            if let [month] = chunk {
+                if !options.notes.is_empty() {
+                    let lines = output.split_off(chunk_start);
+                    output.push_str(&month.with_notes(options, &lines));
+                }
+            }
+        }
+
+        if options.invert && show_color(options.color) {
+            // wrap each line individually so pagers don't lose the style between lines
+            output = output
+                .lines()
+                .map(|line| format!("\x1B[7m{}\x1B[27m\n", line))
+                .collect();
+        }
+
+        output
+    }
+}
+
+impl MonthRange {
+    /// Renders the months as an SVG image laid out in `options.columns` columns. Days are `<text>`
+    /// elements with classes (`day`, `weekend`, `today`, `highlight`) that can be styled with CSS.
+    fn print_svg(&self, options: &RenderOptions) -> String {
+        let columns = self.months.len().clamp(1, options.columns);
+        let rows = self.months.len().div_ceil(options.columns).max(1);
+        let width = columns * SVG_MONTH_WIDTH + (columns - 1) * SVG_MONTH_GAP;
+        let height = rows * SVG_MONTH_HEIGHT + (rows - 1) * SVG_MONTH_GAP;
+
+        // text is anchored at the center of each cell, slightly below the middle
+        let text = |class: &str, column: usize, row: usize, content: &str| {
+            format!(
+                "    <text class=\"{}\" x=\"{}\" y=\"{}\">{}</text>\n",
+                class,
+                column * SVG_CELL_WIDTH + SVG_CELL_WIDTH / 2,
+                row * SVG_CELL_HEIGHT + SVG_CELL_HEIGHT * 3 / 4,
+                content
+            )
+        };
+
+        let mut output = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" class=\"calendar\">\n",
+            width, height
+        );
+        output.push_str(concat!(
+            "  <style>\n",
+            "    text { font-family: monospace; font-size: 12px; text-anchor: middle; }\n",
+            "    .month-header { font-weight: bold; }\n",
+            "    .today { text-decoration: underline; font-weight: bold; }\n",
+            "    .holiday { font-style: italic; }\n",
+            "  </style>\n"
+        ));
+
+        for (index, month) in self.months.iter().enumerate() {
+            let x = (index % options.columns) * (SVG_MONTH_WIDTH + SVG_MONTH_GAP);
+            let y = (index / options.columns) * (SVG_MONTH_HEIGHT + SVG_MONTH_GAP);
+
+            output.push_str(&format!(
+                "  <g class=\"month\" transform=\"translate({}, {})\">\n",
+                x, y
+            ));
+            output.push_str(&format!(
+                "    <text class=\"month-header\" x=\"{}\" y=\"{}\">{}</text>\n",
+                SVG_MONTH_WIDTH / 2,
+                SVG_CELL_HEIGHT * 3 / 4,
+                month.start_date.format("%B %Y")
+            ));
+
+            for (column, weekday) in weekdays_from(month.first_day_of_week).enumerate() {
+                output.push_str(&text(
+                    "weekday-header",
+                    column,
+                    1,
+                    &weekday.to_string()[..2],
+                ));
+            }
+
+            for (week_index, week) in month.weeks.iter().enumerate() {
+                for (column, weekday) in weekdays_from(month.first_day_of_week).enumerate() {
+                    let Some(date) = week.day(weekday) else {
+                        continue;
+                    };
+
+                    let mut class = String::from("day");
+                    if matches!(weekday, Weekday::Sat | Weekday::Sun) {
+                        class.push_str(" weekend");
+                    }
+                    if date == options.current_date {
+                        class.push_str(" today");
+                    }
+                    if options.is_highlighted(date) {
+                        class.push_str(" highlight");
+                    }
+                    if options.holidays.contains_key(&date) {
+                        class.push_str(" holiday");
+                    }
+
+                    output.push_str(&text(
+                        &class,
+                        column,
+                        week_index + 2,
+                        &date.day().to_string(),
+                    ));
+                }
+            }
+
+            output.push_str("  </g>\n");
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+
+    /// Renders each month as a CSV table for importing into spreadsheets: the month's name, a row
+    /// of weekday names, then a row per week with a column per weekday (empty where the week
+    /// doesn't have that day). Months are separated by a blank line.
+    fn print_grid_csv(&self) -> String {
+        self.months
+            .iter()
+            .map(|month| {
+                let weekdays = weekdays_from(month.first_day_of_week).collect_vec();
+                let mut output = format!("{}\n", month.start_date.format("%B %Y"));
+
+                let mut names = weekdays
+                    .iter()
+                    .map(|weekday| weekday.to_string()[..2].to_string());
+                output.push_str(&names.join(","));
+                output.push('\n');
+
+                for week in &month.weeks {
+                    let mut days = weekdays.iter().map(|&weekday| match week.day(weekday) {
+                        Some(date) => date.day().to_string(),
+                        None => String::new(),
+                    });
+
+                    output.push_str(&days.join(","));
+                    output.push('\n');
+                }
+
+                output
+            })
+            .join("\n")
+    }
+}
+
+/// Iterates over the months in the range.
+///
+/// ```
+/// use cal::MonthRange;
+/// use chrono::{NaiveDate, Weekday};
+///
+/// let start_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+/// let end_date = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+/// let month_range = MonthRange::new(start_date, end_date, Weekday::Mon);
+///
+/// for month in &month_range {
+///     println!("{}", month);
+/// }
+///
+/// assert_eq!(month_range.into_iter().count(), 3);
+/// ```
+impl<'a> IntoIterator for &'a MonthRange {
+    type Item = &'a Month;
+    type IntoIter = std::slice::Iter<'a, Month>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.months.iter()
+    }
+}
+
+#[derive(Debug)]
+pub struct Month {
+    pub start_date: NaiveDate,
+    pub first_day_of_week: Weekday,
+    pub weeks: Vec<Week>,
+}
+
+impl Month {
+    /// Appends the month's notes to its rendered `lines`, one per line (e.g. ` 8  Birthday`)
+    /// starting beside the header. Notes beyond the last line get lines of their own, indented to
+    /// line up with the rest.
+    fn with_notes(&self, options: &RenderOptions, lines: &str) -> String {
+        let notes = options
+            .notes
+            .range(self.start_date..=self.end_date())
+            .map(|(&date, note)| {
+                let day = format!("{:>2}", date.day());
+                let day = if show_color(options.color) {
+                    options.highlight_style_for(date).paint(&day)
+                } else {
+                    day
+                };
+
+                format!("{}  {}", day, note)
+            })
+            .collect_vec();
+        let blank = " ".repeat(options.month_width());
+
+        lines
+            .lines()
+            .zip_longest(&notes)
+            .map(|line| match line {
+                EitherOrBoth::Both(line, note) => format!("{}  {}\n", line, note),
+                EitherOrBoth::Left(line) => format!("{}\n", line),
+                EitherOrBoth::Right(note) => format!("{}  {}\n", blank, note),
+            })
+            .collect()
+    }
+
+    /// Returns the last day displayed for this month.
+    pub fn end_date(&self) -> NaiveDate {
+        self.days().last().unwrap_or(self.start_date)
+    }
+
+    /// Returns an iterator over every day displayed for this month, in chronological order.
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        let first_day_of_week = self.first_day_of_week;
+
+        self.weeks
+            .iter()
+            .flat_map(move |week| week.days(first_day_of_week))
+    }
+
+    /// Returns `true` if `date` is one of the days displayed for this month.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.weeks.iter().any(|week| week.contains(date))
+    }
+
+    /// Returns the first ISO week containing days of this month that belongs to a different ISO
+    /// week-year than the month's calendar year (e.g. 2025-W01 for December 30th, 2024).
+    fn iso_week_in_other_year(&self) -> Option<chrono::IsoWeek> {
+        self.days()
+            .map(|day| day.iso_week())
+            .find(|week| week.year() != self.start_date.year())
+    }
+
+    fn print_header(&self, options: &RenderOptions, show_year: bool, output: &mut String) {
+        // the ISO form always includes the year, since it isn't useful without it
+        let header = match (options.header_format, show_year) {
+            (HeaderFormat::Name, true) => self.start_date.format("%B %Y").to_string(),
+            (HeaderFormat::Name, false) => self.start_date.format("%B").to_string(),
+            (HeaderFormat::Iso, _) => self.start_date.format("%Y-%m").to_string(),
+            (HeaderFormat::Both, true) => self.start_date.format("%Y-%m (%b %Y)").to_string(),
+            (HeaderFormat::Both, false) => self.start_date.format("%Y-%m (%b)").to_string(),
+        };
+        let header = match format!("{} ({})", header, self.days().count()) {
+            counted if options.show_day_count && counted.width() <= options.month_width() => {
+                counted
+            }
+            _ => header,
+        };
+        let header = match self
+            .iso_week_in_other_year()
+            .filter(|_| options.show_iso_week_year)
+        {
+            Some(week) => {
+                let note = format!(" / {}-W{:02}", week.year(), week.week());
+                let short_header = match options.header_format {
+                    HeaderFormat::Name => header.replacen(
+                        &self.start_date.format("%B").to_string(),
+                        &self.start_date.format("%b").to_string(),
+                        1,
+                    ),
+                    HeaderFormat::Iso | HeaderFormat::Both => header.clone(),
+                };
+
+                [
+                    format!("{}{}", header, note),
+                    format!("{}{}", short_header, note),
+                ]
+                .into_iter()
+                .find(|annotated| annotated.width() <= options.month_width())
+                .unwrap_or(header)
+            }
+            None => header,
+        };
+
+        let padded = pad_to_width(&header, options.month_width(), fmt::Alignment::Center);
+
+        if show_color(options.color) {
+            let is_highlighted = options
+                .highlight_month
+                .is_some_and(|date| self.contains(date));
+            let style = if is_highlighted {
+                &options.theme.highlight
+            } else {
+                &options.theme.header
+            };
+
+            // style only the text so that the padding stays unstyled
+            output.push_str(&padded.replacen(&header, &style.paint(&header), 1));
+        } else {
+            output.push_str(&padded);
+        }
+    }
+
+    /// Pushes the rule under the weekday header (without a trailing newline), as wide as the
+    /// month's columns.
+    fn print_header_rule(&self, rule: HeaderRule, options: &RenderOptions, output: &mut String) {
+        let rule = rule.char().to_string().repeat(options.month_width());
+
+        if show_color(options.color) {
+            output.push_str(&options.theme.weekday_header.paint(&rule));
+        } else {
+            output.push_str(&rule);
+        }
+    }
+
+    fn print_weekday_header(&self, options: &RenderOptions, output: &mut String) {
+        if options.uses_gutters() {
+            output.push(' ');
+        }
+
+        let weekdays = weekdays_from(self.first_day_of_week)
+            .map(|weekday| {
+                let name = weekday.to_string().chars().take(2).collect::<String>();
+                let name = pad_to_width(&name, options.cell_width(), fmt::Alignment::Right);
+
+                if options.is_today_column(weekday) {
+                    options.theme.today_column.paint(&name)
+                } else {
+                    name
+                }
+            })
+            .collect_vec();
+        let weekdays = if options.fold_weekends {
+            fold_weekend(weekdays, self.first_day_of_week, |saturday, sunday| {
+                format!("{}/{}", saturday, sunday)
+            })
+        } else {
+            weekdays
+        }
+        .join(options.day_gap());
+
+        if show_color(options.color) {
+            output.push_str(&options.theme.weekday_header.paint(&weekdays));
+        } else {
+            output.push_str(&weekdays);
+        }
+
+        if options.uses_gutters() {
+            output.push(' ');
+        }
+    }
+
+    fn print(&self, options: &RenderOptions) -> String {
+        let mut output = String::new();
+
+        self.print_header(options, true, &mut output);
+        output.push('\n');
+        self.print_weekday_header(options, &mut output);
+        output.push('\n');
+        if let Some(rule) = options.header_rule {
+            self.print_header_rule(rule, options, &mut output);
+            output.push('\n');
+        }
+
+        let weeks = options.force_weeks.unwrap_or(self.weeks.len());
+
+        for week_index in 0..weeks {
+            match self.weeks.get(week_index) {
+                Some(week) => week.print(options, self.first_day_of_week, &mut output),
+                None => output.push_str(&" ".repeat(options.month_width())),
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.print(&RenderOptions::default()))
+    }
+}
+
+fn format_date(
+    options: &RenderOptions,
+    first_day_of_week: Weekday,
+    date: Option<NaiveDate>,
+) -> String {
+    match date {
+        Some(d) => {
+            let day = cell_text(options, first_day_of_week, d);
+
+            if !show_color(options.color) {
+                day
+            } else if d == options.current_date {
+                if options.highlight_style == Some(HighlightStyle::Box) {
+                    day
+                } else if options.invert {
+                    // when the whole calendar is inverted, today stands out by being in normal
+                    // video regardless of the theme
+                    Style::sgr("27", "7").paint(&day)
+                } else {
+                    options.today_style().paint(&day)
+                }
+            } else {
+                let day = if options.is_highlighted(d) {
+                    options.highlight_style_for(d).paint(&day)
+                } else if options.holidays.contains_key(&d) {
+                    options.theme.holiday.paint(&day)
+                } else if matches!(d.weekday(), Weekday::Sat | Weekday::Sun) {
+                    options.theme.weekend.paint(&day)
+                } else {
+                    day
+                };
+
+                if d < options.current_date {
+                    options.past_style().paint(&day)
+                } else {
+                    day
+                }
+            }
+        }
+        None => match options.fill_empty {
+            Some(fill) => fill.to_string().repeat(options.cell_width()),
+            None => " ".repeat(options.cell_width()),
+        },
+    }
+}
+
+/// Shown in place of weekend days with `--emoji-weekends`.
+const WEEKEND_EMOJI: &str = "🎉";
+
+/// Returns the unstyled text of `date`'s cell, padded to the cell width.
+fn cell_text(options: &RenderOptions, first_day_of_week: Weekday, date: NaiveDate) -> String {
+    let width = options.cell_width();
+
+    if options.emoji_weekends && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        // zero padding only applies to numbers
+        let align = match options.day_pad {
+            DayPad::Space | DayPad::Zero => fmt::Alignment::Right,
+            DayPad::Left => fmt::Alignment::Left,
+        };
+
+        return pad_to_width(WEEKEND_EMOJI, width, align);
+    }
+
+    let content = match options.cell_content {
+        CellContent::Day => date.day(),
+        CellContent::WeekdayIndex => {
+            (date.weekday().num_days_from_monday() + 7 - first_day_of_week.num_days_from_monday())
+                % 7
+                + 1
+        }
+        CellContent::DayOfYear => date.ordinal(),
+    };
+
+    match options.day_pad {
+        DayPad::Space => format!("{:>width$}", content),
+        DayPad::Zero => format!("{:0width$}", content),
+        DayPad::Left => format!("{:<width$}", content),
+    }
+}
+
+/// Pads `text` with spaces to fill `width` terminal columns, like `format!("{:^width$}", text)`
+/// but by its displayed width rather than its number of characters, so that wide (e.g. CJK and
+/// most emoji) and combining characters line up.
+fn pad_to_width(text: &str, width: usize, align: fmt::Alignment) -> String {
+    let padding = width.saturating_sub(text.width());
+    let (before, after) = match align {
+        fmt::Alignment::Left => (0, padding),
+        fmt::Alignment::Right => (padding, 0),
+        fmt::Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+
+    format!("{}{}{}", " ".repeat(before), text, " ".repeat(after))
+}
+
+/// Decides whether to use color. `FORCE_COLOR` wins over `NO_COLOR`, which wins over `color`
+/// (the command line flag, falling back to the config file).
+fn show_color(color: ColorWhen) -> bool {
+    // Check for the environment variable override first
+    if let Ok(val) = std::env::var("FORCE_COLOR") {
+        match val.as_str() {
+            "1" | "true" => return true,
+            "0" | "false" => return false,
+            _ => {}
+        }
+    }
+
+    // https://no-color.org: any non-empty value disables color
+    if std::env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty()) {
+        return false;
+    }
+
+    match color {
+        ColorWhen::Always => true,
+        ColorWhen::Auto => is_interactive(),
+        ColorWhen::Never => false,
+    }
+}
+
+/// Settles `--color auto` by whether the output is going to a terminal: `output` (from
+/// `--output`) when given, otherwise stdout. Files aren't terminals, so they only get color when it
+/// was explicitly requested, even if stdout is a terminal.
+fn resolve_color(
+    color: Option<ColorWhen>,
+    output: Option<&Path>,
+    stdout_is_terminal: bool,
+) -> ColorWhen {
+    let sink_is_terminal = output.is_none() && stdout_is_terminal;
+
+    match color.unwrap_or(ColorWhen::Auto) {
+        ColorWhen::Auto if sink_is_terminal => ColorWhen::Always,
+        ColorWhen::Auto => ColorWhen::Never,
+        color => color,
+    }
+}
+
+fn is_interactive() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+#[derive(Debug)]
+pub struct Week {
+    pub monday: Option<NaiveDate>,
+    pub tuesday: Option<NaiveDate>,
+    pub wednesday: Option<NaiveDate>,
+    pub thursday: Option<NaiveDate>,
+    pub friday: Option<NaiveDate>,
+    pub saturday: Option<NaiveDate>,
+    pub sunday: Option<NaiveDate>,
+}
+
+impl Week {
+    fn new() -> Week {
+        Week {
+            monday: None,
+            tuesday: None,
+            wednesday: None,
+            thursday: None,
+            friday: None,
+            saturday: None,
+            sunday: None,
+        }
+    }
+
+    /// Builds a week from `dates`, placing each one in the slot for its weekday regardless of which
+    /// month it falls in. Slots without a date are left empty. Returns `None` if the dates don't all
+    /// fall within the same week (starting on `first_day_of_week`).
+    ///
+    /// ```
+    /// use cal::Week;
+    /// use chrono::{NaiveDate, Weekday};
+    ///
+    /// let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+    /// let week = Week::from_dates(&[date(2, 29), date(3, 2)], Weekday::Mon).unwrap();
+    ///
+    /// assert_eq!(week.day(Weekday::Thu), Some(date(2, 29)));
+    /// assert_eq!(week.day(Weekday::Fri), None);
+    /// assert!(Week::from_dates(&[date(3, 3), date(3, 4)], Weekday::Mon).is_none());
+    /// ```
+    pub fn from_dates(dates: &[NaiveDate], first_day_of_week: Weekday) -> Option<Week> {
+        if !dates
+            .iter()
+            .map(|&date| week_index(date, first_day_of_week))
+            .all_equal()
+        {
+            return None;
+        }
+
+        let mut week = Week::new();
+        for &date in dates {
+            match date.weekday() {
+                Weekday::Mon => week.monday = Some(date),
+                Weekday::Tue => week.tuesday = Some(date),
+                Weekday::Wed => week.wednesday = Some(date),
+                Weekday::Thu => week.thursday = Some(date),
+                Weekday::Fri => week.friday = Some(date),
+                Weekday::Sat => week.saturday = Some(date),
+                Weekday::Sun => week.sunday = Some(date),
+            }
+        }
+
+        Some(week)
+    }
+
+    /// Returns the date in the slot for `weekday`, if any.
+    pub fn day(&self, weekday: Weekday) -> Option<NaiveDate> {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+
+    /// Returns an iterator over the days in this week, skipping empty slots, starting from
+    /// `first_day_of_week`.
+    pub fn days(&self, first_day_of_week: Weekday) -> impl Iterator<Item = NaiveDate> + '_ {
+        weekdays_from(first_day_of_week).filter_map(|weekday| self.day(weekday))
+    }
+
+    /// Returns `true` if `date` occupies one of this week's slots.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.day(date.weekday()) == Some(date)
+    }
+
+    /// Returns the earliest date in this week, skipping empty slots (e.g. the 1st of a month that
+    /// starts mid-week).
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        self.days(Weekday::Mon).min()
+    }
+
+    /// Returns the ISO 8601 week number of the week's first day, or `None` if the week is empty.
+    pub fn iso_week_number(&self) -> Option<u32> {
+        self.first_day().map(|date| date.iso_week().week())
+    }
+
+    /// Returns the date that would fill the (empty) slot for `weekday`, counting from the days
+    /// present in this week, e.g. Feb 29 before a March that starts on a Friday.
+    fn adjacent_day(&self, weekday: Weekday, first_day_of_week: Weekday) -> Option<NaiveDate> {
+        let (index, day) = weekdays_from(first_day_of_week)
+            .enumerate()
+            .find_map(|(index, weekday)| self.day(weekday).map(|day| (index, day)))?;
+        let slot = weekdays_from(first_day_of_week).position(|slot| slot == weekday)?;
+
+        day.checked_add_signed(chrono::Duration::days(slot as i64 - index as i64))
+    }
+
+    /// Returns `true` if none of the slots hold a date.
+    pub fn is_empty(&self) -> bool {
+        self.monday.is_none()
+            && self.tuesday.is_none()
+            && self.wednesday.is_none()
+            && self.thursday.is_none()
+            && self.friday.is_none()
+            && self.saturday.is_none()
+            && self.sunday.is_none()
+    }
+
+    /// Returns the text of the cell for `weekday`, along with any plain text markers for the
+    /// gutters on either side of it.
+    fn cell(
+        &self,
+        options: &RenderOptions,
+        first_day_of_week: Weekday,
+        weekday: Weekday,
+    ) -> (String, Option<(char, char)>) {
+        match self.day(weekday) {
+            // ISO weeks start on Monday, the `W` goes in the gutter before the number
+            Some(day) if options.weeknum_in_cells && weekday == Weekday::Mon => {
+                let width = options.cell_width();
+
+                (
+                    format!("{:0width$}", day.iso_week().week()),
+                    Some(('W', ' ')),
+                )
+            }
+            None if options.adjacent_days => {
+                match self.adjacent_day(weekday, first_day_of_week) {
+                    Some(day) if show_color(options.color) => (
+                        options.theme.adjacent_day.paint(&cell_text(
+                            options,
+                            first_day_of_week,
+                            day,
+                        )),
+                        None,
+                    ),
+                    Some(day) => {
+                        // a run of adjacent days shares one pair of parentheses
+                        let open =
+                            if weekday == first_day_of_week || self.day(weekday.pred()).is_some() {
+                                '('
+                            } else {
+                                ' '
+                            };
+                        let close = if weekday == first_day_of_week.pred()
+                            || self.day(weekday.succ()).is_some()
+                        {
+                            ')'
+                        } else {
+                            ' '
+                        };
+
+                        (
+                            cell_text(options, first_day_of_week, day),
+                            Some((open, close)),
+                        )
+                    }
+                    None => (format_date(options, first_day_of_week, None), None),
+                }
+            }
+            day => {
+                let marker = day.and_then(|day| options.plain_marker(day));
+
+                (format_date(options, first_day_of_week, day), marker)
+            }
+        }
+    }
+
+    #[tracing::instrument]
+    fn print(&self, options: &RenderOptions, first_day_of_week: Weekday, output: &mut String) {
+        let highlight_row =
+            options.highlight_current_week && options.is_current_week(self, first_day_of_week);
+
+        let cells = weekdays_from(first_day_of_week).map(|weekday| {
+            let (cell, marker) = self.cell(options, first_day_of_week, weekday);
+
+            // the current week's own background already covers the column, and the column's
+            // background would end it partway through the row
+            if options.is_today_column(weekday) && !highlight_row {
+                (options.theme.today_column.paint(&cell), marker)
+            } else {
+                (cell, marker)
+            }
+        });
+
+        let cells = if options.fold_weekends {
+            fold_weekend(
+                cells.collect(),
+                first_day_of_week,
+                |(saturday, saturday_marker), (sunday, sunday_marker)| {
+                    let is_blank = |cell: &str| strip_ansi(cell).trim().is_empty();
+                    let separator = if is_blank(&saturday) || is_blank(&sunday) {
+                        ' '
+                    } else {
+                        '/'
+                    };
+                    let marker = match (saturday_marker, sunday_marker) {
+                        (Some((open, close)), None) | (None, Some((open, close))) => {
+                            Some((open, close))
+                        }
+                        (Some((open, _)), Some((_, close))) => Some((open, close)),
+                        (None, None) => None,
+                    };
+
+                    (format!("{}{}{}", saturday, separator, sunday), marker)
+                },
+            )
+        } else {
+            cells.collect()
+        };
+
+        let start = output.len();
+        push_cells(
+            cells.into_iter(),
+            options.day_gap(),
+            options.uses_gutters(),
+            output,
+        );
+
+        if highlight_row {
+            if show_color(options.color) {
+                let row = output.split_off(start);
+                output.push_str(&options.theme.current_week.paint(&row));
+            } else if output[start..].starts_with(' ') {
+                // the leading gutter is left alone if it already holds today's marker
+                output.replace_range(start..start + 1, ">");
+            }
+        }
+    }
+}
+
+/// Merges the Saturday and Sunday entries of a row in display order into one, when they are next
+/// to each other (i.e. the week doesn't start on Sunday).
+fn fold_weekend<T>(
+    mut row: Vec<T>,
+    first_day_of_week: Weekday,
+    merge: impl FnOnce(T, T) -> T,
+) -> Vec<T> {
+    let saturday = weekdays_from(first_day_of_week)
+        .position(|weekday| weekday == Weekday::Sat)
+        .expect("every week has a Saturday");
+
+    if saturday + 1 < row.len() {
+        let sunday = row.remove(saturday + 1);
+        let saturday_cell = row.remove(saturday);
+        row.insert(saturday, merge(saturday_cell, sunday));
+    }
+
+    row
+}
+
+/// Returns the seven weekdays in display order, starting from `first_day_of_week`.
+fn weekdays_from(first_day_of_week: Weekday) -> impl Iterator<Item = Weekday> {
+    std::iter::successors(Some(first_day_of_week), |weekday| Some(weekday.succ())).take(7)
+}
+
+/// Pushes a row of day cells separated by `gap`.
+///
+/// Each cell may carry a pair of marker characters that replace the gap on either side of it,
+/// where a space means the gap is left alone. When `outer_gutters` is set, the row also gets a
+/// gutter before the first cell and after the last one so that markers on the edges have
+/// somewhere to go.
+fn push_cells(
+    cells: impl Iterator<Item = (String, Option<(char, char)>)>,
+    gap: &str,
+    outer_gutters: bool,
+    output: &mut String,
+) {
+    let mut previous_close = None;
+
+    for (index, (cell, marker)) in cells.enumerate() {
+        if index > 0 || outer_gutters {
+            match (previous_close, marker) {
+                (Some(_), Some(_)) => output.push('|'),
+                (Some(close), None) => output.push(close),
+                (None, Some((open, _))) => output.push(open),
+                (None, None) if index > 0 => output.push_str(gap),
+                (None, None) => output.push(' '),
+            }
+        }
+
+        output.push_str(&cell);
+        previous_close = marker.map(|(_, close)| close).filter(|&close| close != ' ');
+    }
+
+    if outer_gutters {
+        output.push(previous_close.unwrap_or(' '));
+    }
+}
+
+/// Builds a month from its days, or `None` if there are no days to display.
+fn build_month(days: Vec<NaiveDate>, first_day_of_week: Weekday) -> Option<Month> {
+    let start_date = *days.first()?;
+
+    Some(Month {
+        start_date,
+        first_day_of_week,
+        weeks: build_weeks(days, first_day_of_week),
+    })
+}
+
+/// Splits a contiguous run of days into weeks. Days are not grouped by month, so a week can
+/// contain the end of one month and the start of the next (e.g. `29 30 31  1  2  3  4`).
+fn build_weeks(days: impl IntoIterator<Item = NaiveDate>, first_day_of_week: Weekday) -> Vec<Week> {
+    days.into_iter()
+        .group_by(|&day| week_index(day, first_day_of_week))
+        .into_iter()
+        .map(|(_, days)| {
+            Week::from_dates(&days.collect_vec(), first_day_of_week)
+                .expect("days are grouped by week")
+        })
+        .collect()
+}
+
+#[tracing::instrument]
+fn build_month_range(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    first_day_of_week: Weekday,
+) -> MonthRange {
+    build_month_range_from_ranges(&[(start_date, end_date)], first_day_of_week)
+}
+
+/// Builds the months covering the union of the given (possibly overlapping or non-contiguous)
+/// date ranges.
+#[tracing::instrument]
+fn build_month_range_from_ranges(
+    date_ranges: &[(NaiveDate, NaiveDate)],
+    first_day_of_week: Weekday,
+) -> MonthRange {
+    let dates: BTreeSet<NaiveDate> = date_ranges
+        .iter()
+        .flat_map(|&(start_date, end_date)| date_range(start_date, end_date))
+        .collect();
+
+    let months: Vec<Month> = dates
+        .into_iter()
+        .group_by(|&date| (date.year(), date.month()))
+        .into_iter()
+        .filter_map(|((_year, _month), group)| build_month(group.collect(), first_day_of_week))
+        .collect();
+
+    for month in &months {
+        debug!(
+            "Built month {} ({} - {}, {} weeks)",
+            month.start_date.format("%Y-%m"),
+            month.start_date,
+            month.end_date(),
+            month.weeks.len()
+        );
+    }
+
+    MonthRange { months }
+}
+
+/// Identifies the week (starting on `first_day_of_week`) containing `date`, as the day number of
+/// the week's first day counted from January 1st, 1 CE.
+///
+/// Unlike `date.week(first_day_of_week).first_day()` this doesn't panic for the first and last
+/// weeks that `NaiveDate` supports, whose first day may not be representable.
+fn week_index(date: NaiveDate, first_day_of_week: Weekday) -> i32 {
+    let days_into_week =
+        (date.weekday().num_days_from_monday() + 7 - first_day_of_week.num_days_from_monday()) % 7;
+
+    date.num_days_from_ce() - days_into_week as i32
+}
+
+/// Iterates over the days from `start` through `end` (inclusive), which is empty if `start` is
+/// after `end`. Stops at the last date `NaiveDate` supports.
+fn date_range(start: NaiveDate, end: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    std::iter::successors((start <= end).then_some(start), move |&d| {
+        if d < end {
+            d.succ_opt()
+        } else {
+            None
+        }
+    })
+}
+
+/// Applies `style` to the years and quarters of `date_input` that were given without an `FY`
+/// prefix, which are parsed as calendar years. Months are the same in either kind of year, so they
+/// are left alone.
+fn with_default_year_style(style: YearStyle, date_input: DateInput) -> DateInput {
+    let restyle = |year: Year| match year.style {
+        YearStyle::Calendar => Year { style, ..year },
+        YearStyle::Fiscal => year,
+    };
+
+    match date_input {
+        DateInput::Year(year) => DateInput::Year(restyle(year)),
+        DateInput::YearQuarter(year, quarter) => DateInput::YearQuarter(restyle(year), quarter),
+        DateInput::YearQuarterRange(year, start, end) => {
+            DateInput::YearQuarterRange(restyle(year), start, end)
+        }
+        DateInput::CurrentYearQuarter(YearStyle::Calendar, quarter) => {
+            DateInput::CurrentYearQuarter(style, quarter)
+        }
+        DateInput::QuarterOffset(date_input, offset) => DateInput::QuarterOffset(
+            Box::new(with_default_year_style(style, *date_input)),
+            offset,
+        ),
+        date_input => date_input,
+    }
+}
+
+/// Resolves the parts of the date input that depend on the current date: two digit years are
+/// expanded into the current century, and bare quarters are assigned the current year. Fails when a
+/// relative month lands outside of the supported range.
+#[tracing::instrument(level = "debug", ret)]
+fn resolve_date_input(
+    current_date: NaiveDate,
+    fiscal_year_start: u32,
+    year_pivot: Option<u32>,
+    date_input: DateInput,
+) -> Result<DateInput, CalError> {
+    let date_input = match date_input {
+        DateInput::Year(year) => {
+            let updated_year = normalize_short_year(current_date, year_pivot, year.year);
+
+            DateInput::Year(Year {
+                year: updated_year,
+                ..year
+            })
+        }
+        DateInput::YearMonth(year, month) => {
+            let updated_year = normalize_short_year(current_date, year_pivot, year.year);
+
+            DateInput::YearMonth(
+                Year {
+                    year: updated_year,
+                    ..year
+                },
+                month,
+            )
+        }
+        DateInput::YearQuarter(year, quarter) => {
+            let updated_year = normalize_short_year(current_date, year_pivot, year.year);
+
+            DateInput::YearQuarter(
+                Year {
+                    year: updated_year,
+                    ..year
+                },
+                quarter,
+            )
+        }
+        DateInput::YearQuarterRange(year, start, end) => {
+            let updated_year = normalize_short_year(current_date, year_pivot, year.year);
+
+            DateInput::YearQuarterRange(
+                Year {
+                    year: updated_year,
+                    ..year
+                },
+                start,
+                end,
+            )
+        }
+        DateInput::CurrentYearQuarter(style, quarter) => {
+            let year = determine_current_year(current_date, style, fiscal_year_start);
+
+            DateInput::YearQuarter(Year { style, year }, quarter)
+        }
+        DateInput::Range(start_date, end_date) => DateInput::Range(start_date, end_date),
+        DateInput::CurrentYearRange(start, end) => {
+            let (start_date, end_date) = month_day_range(current_date.year(), start, end)?;
+
+            DateInput::Range(start_date, end_date)
+        }
+        DateInput::RelativeMonth(offset) => {
+            let date = add_months(first_day_of_month(current_date), i64::from(offset))?;
+
+            DateInput::YearMonth(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: date.year(),
+                },
+                date.month(),
+            )
+        }
+        DateInput::QuarterOffset(date_input, offset) => {
+            match resolve_date_input(current_date, fiscal_year_start, year_pivot, *date_input)? {
+                DateInput::YearQuarter(year, quarter) => {
+                    // counted in i64 so that shifting the largest years can't overflow
+                    let quarters = i64::from(year.year) * 4
+                        + i64::from(quarter.months_into_year() / 3)
+                        + i64::from(offset);
+                    let month = quarters.rem_euclid(4) as u32 * 3 + 1;
+                    let shifted_year = i32::try_from(quarters.div_euclid(4)).map_err(|_| {
+                        let prefix = match year.style {
+                            YearStyle::Calendar => "",
+                            YearStyle::Fiscal => "FY",
+                        };
+
+                        CalError::OutOfRange(format!(
+                            "{}{}Q{}",
+                            prefix,
+                            quarters.div_euclid(4),
+                            quarters.rem_euclid(4) + 1
+                        ))
+                    })?;
+
+                    DateInput::YearQuarter(
+                        Year {
+                            year: shifted_year,
+                            ..year
+                        },
+                        Quarter::from_month(month, YearStyle::Calendar, 1),
+                    )
+                }
+                date_input => {
+                    return Err(CalError::InvalidDateInput(format!(
+                        "Only quarters can be offset, got: {:?}",
+                        date_input
+                    )))
+                }
+            }
+        }
+    };
+
+    Ok(date_input)
+}
+
+/// Returns the span from the start of the current year (or quarter, if `quarter_to_date`) through
+/// `current_date`.
+fn determine_to_date_range(
+    current_date: NaiveDate,
+    style: YearStyle,
+    fiscal_year_start: u32,
+    quarter_to_date: bool,
+) -> Result<DateInput, CalError> {
+    let year = determine_current_year(current_date, style, fiscal_year_start);
+    let year_start_date = match style {
+        YearStyle::Calendar => month_start(year, 1)?,
+        YearStyle::Fiscal => fiscal_year_start_date(year, fiscal_year_start)?,
+    };
+
+    let start_date = if quarter_to_date {
+        let quarter = Quarter::from_month(current_date.month(), style, fiscal_year_start);
+
+        add_months(year_start_date, i64::from(quarter.months_into_year()))?
+    } else {
+        year_start_date
+    };
+
+    Ok(DateInput::Range(start_date, current_date))
+}
+
+#[tracing::instrument(ret)]
+fn determine_date_ranges(
+    current_date: NaiveDate,
+    args: Arguments,
+) -> Result<Vec<(NaiveDate, NaiveDate)>, CalError> {
+    // `--year` and `--month` are mutually exclusive with the date_input field, so we can safely
+    // normalize `--year` and `--month` into DateInput::YearMonth without issue
+    let args = match (args.year, args.month) {
+        (Some(year), Some(month)) => {
+            let date = NaiveDate::from_ymd_opt(year, month, 1)
+                .ok_or_else(|| CalError::OutOfRange(format!("{}-{:02}", year, month)))?;
+
+            let date_input = vec![DateInput::YearMonth(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: date.year(),
+                },
+                date.month(),
+            )];
+
+            Arguments { date_input, ..args }
+        }
+        (Some(year), None) => {
+            let style = if args.fiscal {
+                YearStyle::Fiscal
+            } else {
+                YearStyle::Calendar
+            };
+
+            // fiscal years that don't start in January begin in the previous calendar year
+            let first_calendar_year = match style {
+                YearStyle::Fiscal if args.fiscal_year_start != 1 => year.checked_sub(1),
+                _ => Some(year),
+            };
+            first_calendar_year
+                .and_then(|first_calendar_year| NaiveDate::from_ymd_opt(first_calendar_year, 1, 1))
+                .ok_or_else(|| CalError::OutOfRange(year.to_string()))?;
+
+            let date_input = vec![DateInput::Year(Year { style, year })];
+
+            Arguments { date_input, ..args }
+        }
+        _ => args,
+    };
+
+    let args = match (&args.start_date, args.num_months) {
+        (Some(DateInput::YearMonth(year, month)), Some(num_months)) => {
+            let start_date = month_start(year.year, *month)?;
+            let end_date = add_months(start_date, i64::from(num_months) - 1)?;
+
+            let date_input = vec![DateInput::Range(
+                start_date,
+                last_day_of_month_for(end_date),
+            )];
+
+            Arguments { date_input, ..args }
+        }
+        _ => args,
+    };
+
+    let args = if let Some(date) = args.around {
+        let date_input = vec![DateInput::YearMonth(
+            Year {
+                style: YearStyle::Calendar,
+                year: date.year(),
+            },
+            date.month(),
+        )];
+
+        Arguments {
+            date_input,
+            months_before: args.radius,
+            months_after: args.radius,
+            ..args
+        }
+    } else {
+        args
+    };
+
+    let args = if args.three {
+        Arguments {
+            months_before: Some(1),
+            months_after: Some(1),
+            ..args
+        }
+    } else if args.one {
+        Arguments {
+            context: None,
+            ..args
+        }
+    } else {
+        args
+    };
+
+    let args = if args.whole_year {
+        let date_input = if args.date_input.is_empty() {
+            vec![DateInput::Year(Year {
+                style: YearStyle::Calendar,
+                year: current_date.year(),
+            })]
+        } else {
+            args.date_input
+                .into_iter()
+                .map(|date_input| match date_input {
+                    DateInput::YearMonth(year, _) => DateInput::Year(year),
+                    date_input => date_input,
+                })
+                .collect()
+        };
+
+        Arguments { date_input, ..args }
+    } else {
+        args
+    };
+
+    let args = if args.ytd || args.qtd {
+        let style = if args.fiscal {
+            YearStyle::Fiscal
+        } else {
+            YearStyle::Calendar
+        };
+        let date_input = vec![determine_to_date_range(
+            current_date,
+            style,
+            args.fiscal_year_start,
+            args.qtd,
+        )?];
+
+        Arguments { date_input, ..args }
+    } else {
+        args
+    };
+
+    // Now populate `date_input` if it isn't present already
+    let args = if args.date_input.is_empty() {
+        let date_input = vec![DateInput::YearMonth(
+            Year {
+                style: YearStyle::Calendar,
+                year: current_date.year(),
+            },
+            current_date.month(),
+        )];
+
+        // `--context` only widens the default view, explicit dates are shown as requested
+        Arguments {
+            date_input,
+            months_before: args.months_before.or(args.context),
+            months_after: args.months_after.or(args.context),
+            ..args
+        }
+    } else {
+        args
+    };
+
+    let year_start_date = |year: &Year| match year.style {
+        YearStyle::Calendar => month_start(year.year, 1),
+        YearStyle::Fiscal => fiscal_year_start_date(year.year, args.fiscal_year_start),
+    };
+
+    args.date_input
+        .into_iter()
+        .map(|date_input| {
+            let (start_date, end_date) = match date_input {
+                DateInput::Year(year) => {
+                    let start_date = year_start_date(&year)?;
+                    let end_date = last_day_of_month_for(add_months(start_date, 11)?);
+
+                    (start_date, end_date)
+                }
+                DateInput::YearMonth(year, month) => {
+                    // TODO: emit a nice error message if someone tries to use fiscal year and month syntax
+                    let start_date = month_start(year.year, month)?;
+                    let end_date = last_day_of_month_for(start_date);
+
+                    (start_date, end_date)
+                }
+                DateInput::YearQuarter(year, quarter) => {
+                    let start_date = add_months(
+                        year_start_date(&year)?,
+                        i64::from(quarter.months_into_year()),
+                    )?;
+                    let end_date = last_day_of_month_for(add_months(start_date, 2)?);
+
+                    (start_date, end_date)
+                }
+                DateInput::YearQuarterRange(year, start, end) => {
+                    let year_start_date = year_start_date(&year)?;
+                    let start_date =
+                        add_months(year_start_date, i64::from(start.months_into_year()))?;
+                    let end_date = last_day_of_month_for(add_months(
+                        year_start_date,
+                        i64::from(end.months_into_year()) + 2,
+                    )?);
+
+                    (start_date, end_date)
+                }
+                DateInput::Range(start_date, end_date) => (start_date, end_date),
+                DateInput::CurrentYearQuarter(_, _) => {
+                    return Err(CalError::InvalidDateInput(
+                        "Bare quarters must be resolved before determining the date range"
+                            .to_string(),
+                    ))
+                }
+                DateInput::CurrentYearRange(_, _) => {
+                    return Err(CalError::InvalidDateInput(
+                        "Yearless ranges must be resolved before determining the date range"
+                            .to_string(),
+                    ))
+                }
+                DateInput::RelativeMonth(_) => {
+                    return Err(CalError::InvalidDateInput(
+                        "Relative months must be resolved before determining the date range"
+                            .to_string(),
+                    ))
+                }
+                DateInput::QuarterOffset(_, _) => {
+                    return Err(CalError::InvalidDateInput(
+                        "Quarter offsets must be resolved before determining the date range"
+                            .to_string(),
+                    ))
+                }
+            };
+
+            let start_date = match args.months_before {
+                Some(months_before) => {
+                    first_day_of_month(add_months(start_date, -i64::from(months_before))?)
+                }
+                None => start_date,
+            };
+
+            let end_date = match args.months_after {
+                Some(months_after) => {
+                    last_day_of_month_for(add_months(end_date, i64::from(months_after))?)
+                }
+                None => end_date,
+            };
+
+            Ok((start_date, end_date))
+        })
+        .collect()
+}
+
+/// Returns the date `delta` months after `date` (or before it, when negative), wrapping across as
+/// many years as needed. Days past the end of the resulting month are clamped to its last day, so
+/// a month after January 31st is February 28th (or 29th).
+///
+/// The error names the resulting month when it can't be represented.
+fn add_months(date: NaiveDate, delta: i64) -> Result<NaiveDate, CalError> {
+    let months = i64::from(date.year()) * 12 + i64::from(date.month0()) + delta;
+    let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+    let month_start = i32::try_from(year)
+        .ok()
+        .and_then(|year| NaiveDate::from_ymd_opt(year, month, 1))
+        .ok_or_else(|| CalError::OutOfRange(format!("{}-{:02}", year, month)))?;
+    let day = date.day().min(last_day_of_month_for(month_start).day());
+
+    Ok(month_start
+        .with_day(day)
+        .expect("the day is clamped to the month's length"))
+}
+
+/// Returns the 1st of `date`'s month.
+fn first_day_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a 1st")
+}
+
+/// Describes how the displayed dates were chosen, for `--explain`.
+fn explanation(
+    parsed_input: &[DateInput],
+    resolved_input: &[DateInput],
+    (first_day_of_week, source): (Weekday, FirstDayOfWeekSource),
+    date_ranges: &[(NaiveDate, NaiveDate)],
+) -> String {
+    let mut output = String::new();
+
+    if parsed_input.is_empty() {
+        output.push_str("input: none\n");
+    }
+    for (parsed, resolved) in parsed_input.iter().zip(resolved_input) {
+        output.push_str(&format!("input: {:?}\n", parsed));
+        if resolved != parsed {
+            output.push_str(&format!("  resolved to: {:?}\n", resolved));
+        }
+    }
+
+    let source = match source {
+        FirstDayOfWeekSource::Argument => "set by --first-day-of-week",
+        FirstDayOfWeekSource::SystemPreference => "system preference",
+        FirstDayOfWeekSource::Fallback => "default, no preference was detected",
+    };
+    output.push_str(&format!(
+        "first day of week: {} ({})\n",
+        first_day_of_week, source
+    ));
+
+    for (start_date, end_date) in date_ranges {
+        output.push_str(&format!("range: {} through {}\n", start_date, end_date));
+    }
+
+    output
+}
+
+/// Counts the distinct months touched by the given (possibly overlapping) date ranges, without
+/// building them.
+fn count_months(date_ranges: &[(NaiveDate, NaiveDate)]) -> usize {
+    let month_index = |date: NaiveDate| i64::from(date.year()) * 12 + i64::from(date.month0());
+
+    date_ranges
+        .iter()
+        .flat_map(|&(start_date, end_date)| month_index(start_date)..=month_index(end_date))
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+fn last_day_of_month_for(date: NaiveDate) -> NaiveDate {
+    // December is handled directly so the last supported year doesn't need to look at the
+    // (unrepresentable) following January
+    if date.month() == 12 {
+        return NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap();
+    }
+
+    let next_month_start_date = NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap();
+
+    next_month_start_date.pred_opt().unwrap()
+}
+
+/// The version line followed by the build details captured by `build.rs`.
+pub fn version_verbose() -> String {
+    format!(
+        "cal {}\ncommit: {}\nbuild date: {}\ntarget: {}",
+        env!("CARGO_PKG_VERSION"),
+        env!("CAL_GIT_COMMIT"),
+        env!("CAL_BUILD_DATE"),
+        env!("CAL_TARGET"),
+    )
+}
+
+#[tracing::instrument]
+pub fn print(args: Arguments, current_date: NaiveDate) -> Result<String, CalError> {
+    print_with_notices(args, current_date, &mut |message| eprintln!("{}", message))
+}
+
+/// Renders the calendar like [`print`], but hands its notes and warnings to `notices` instead of
+/// printing them to stderr.
+fn print_with_notices(
+    mut args: Arguments,
+    current_date: NaiveDate,
+    notices: &mut dyn FnMut(String),
+) -> Result<String, CalError> {
+    // checked here rather than while rendering, so that every kind of output (including SVG and
+    // the query options) can be verified
+    let verify = args.verify.take();
+    let output = render(args, current_date, notices)?;
+
+    if let Some(expected) = verify {
+        let actual = output_hash(&output);
+
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            return Err(CalError::VerificationFailed { expected, actual });
+        }
+    }
+
+    Ok(output)
+}
+
+/// Renders the output asked for by `args`, see [`print_with_notices`].
+fn render(
+    mut args: Arguments,
+    current_date: NaiveDate,
+    notices: &mut dyn FnMut(String),
+) -> Result<String, CalError> {
+    if args.version_verbose {
+        return Ok(version_verbose());
+    }
+
+    let diff = std::mem::take(&mut args.diff);
+    let is_diff = !diff.is_empty();
+
+    if is_diff {
+        args.date_input = diff;
+    }
+
+    let read_dates = |path: &Option<PathBuf>| match path {
+        Some(path) => std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| parse_highlight_file(&contents))
+            .map_err(|message| CalError::Read {
+                path: path.clone(),
+                message,
+            }),
+        None => Ok(BTreeMap::new()),
+    };
+    let mut highlighted_dates = read_dates(&args.highlight_file)?;
+    let mut notes = BTreeMap::new();
+
+    // notes are marked like highlighted dates, but their labels go beside the month instead of
+    // in the legend
+    for (date, highlighted) in read_dates(&args.overlay_notes)? {
+        if let Some(label) = highlighted.label {
+            notes.insert(date, label);
+        }
+
+        highlighted_dates.entry(date).or_insert(HighlightedDate {
+            color: highlighted.color,
+            label: None,
+        });
+    }
+    let color_depth = args.color_depth.unwrap_or_else(ColorDepth::detect);
+
+    let mut options = RenderOptions {
+        color: resolve_color(args.color, args.output.as_deref(), is_interactive()),
+        color_depth,
+        current_date,
+        show_year_only_once: args.show_year_only_once,
+        show_day_count: args.show_day_count_per_month,
+        show_iso_week_year: args.show_iso_week_year_in_header,
+        header_format: args.header_format,
+        header_rule: args.header_rule,
+        highlight_nth_weekdays: args.highlight_nth_weekday.clone(),
+        highlight_days_of_month: args.highlight_day_of_month.clone(),
+        // the months being compared are always shown next to each other
+        columns: if is_diff {
+            2
+        } else if args.overlay_notes.is_some() {
+            1
+        } else {
+            args.columns as usize
+        },
+        force_weeks: args.force_weeks.map(|weeks| weeks as usize),
+        trim_empty_rows: args.no_empty_trailing_row,
+        quarter_dividers: args.quarter_dividers,
+        group_by_quarter: args.group_by_quarter,
+        fiscal_year_start: args.fiscal.then_some(args.fiscal_year_start),
+        shared_weekday_header: args.shared_weekday_header,
+        highlight_month: args.around,
+        ascii_today_marker: args.ascii_today_marker,
+        weeknum_in_cells: args.append_weeknum_suffix,
+        highlight_style: args.highlight_style,
+        highlight_current_week: args.highlight_current_week,
+        anchor_today_column: args.anchor_today_column,
+        invert: args.invert,
+        past_style: args.past_style,
+        day_pad: args.day_pad,
+        cell_content: args.cell_content,
+        emoji_weekends: args.emoji_weekends,
+        day_gap: args.day_gap,
+        fill_empty: args.fill_empty,
+        adjacent_days: args.trailing_days_from_adjacent_months,
+        holidays: args.holidays.clone(),
+        highlighted_dates,
+        notes,
+        fold_weekends: args.fold_weekends_into_one_column,
+        use_tabs: args.use_tabs,
+        theme: {
+            let mut theme = Theme::from(args.theme).with_depth(color_depth);
+            if let Some(color) = args.highlight_color {
+                theme.highlight = Style {
+                    on: color.foreground(color_depth),
+                    off: "39".to_string(),
+                };
+            }
+
+            theme
+        },
+    };
+    let range_summary = args.range_summary;
+    let repeat = args.repeat.unwrap_or(1) as usize;
+    let align_firsts = args.align_firsts;
+    let continuous = args.continuous;
+    let parsed_input = args.explain.then(|| args.date_input.clone());
+    let date_input: Vec<DateInput> = args
+        .date_input
+        .into_iter()
+        .map(|date_input| {
+            resolve_date_input(
+                current_date,
+                args.fiscal_year_start,
+                args.year_pivot,
+                with_default_year_style(
+                    args.default_year_style.unwrap_or(YearStyle::Calendar),
+                    date_input,
+                ),
+            )
+        })
+        .collect::<Result<_, _>>()?;
+    let explain = parsed_input.map(|parsed_input| (parsed_input, date_input.clone()));
+
+    let args = Arguments { date_input, ..args };
+    if let Some(date) = args.weekday_of {
+        return print_weekday_of(date, args.format);
+    }
+
+    if let Some(is_leap) = args.leap_year_query(current_date) {
+        let year = args.is_leap.flatten().unwrap_or(current_date.year());
+
+        return Ok(match args.format {
+            OutputFormat::Text => is_leap.to_string(),
+            OutputFormat::Json => serde_json::json!({ "year": year, "leap": is_leap }).to_string(),
+            format @ (OutputFormat::Svg | OutputFormat::GridCsv) => {
+                return Err(CalError::UnsupportedFormat(format!(
+                    "--is-leap can't be printed as {}, only as text or json",
+                    format.name()
+                )))
+            }
+        });
+    }
+
+    if args.print_range {
+        let format = args.format;
+
+        return print_date_ranges(&determine_date_ranges(current_date, args)?, format);
+    }
+
+    if !args.count_weekday.is_empty() {
+        let weekdays = args.count_weekday.iter().copied().unique().collect_vec();
+        let format = args.format;
+
+        return print_weekday_counts(
+            &determine_date_ranges(current_date, args)?,
+            &weekdays,
+            format,
+        );
+    }
+
+    if let Some(set) = args.list_holidays {
+        let format = args.format;
+
+        return print_holiday_list(set, &determine_date_ranges(current_date, args)?, format);
+    }
+
+    if args.format == OutputFormat::Json {
+        return Err(CalError::UnsupportedFormat(
+            "calendars can't be printed as json, only as text, svg or grid-csv".to_string(),
+        ));
+    }
+    let format = args.format;
+    let quiet = args.quiet;
+    let max_months = (!args.force).then_some(args.max_months);
+
+    let (first_day_of_week, first_day_of_week_source) =
+        determine_default_first_day_of_week(args.first_day_of_week.or(args.week_start_offset));
+
+    if first_day_of_week_source == FirstDayOfWeekSource::Fallback {
+        notice(
+            quiet,
+            notices,
+            "note: no first day of week preference was detected, defaulting to Monday. \
+             Use `--first-day-of-week` to set it explicitly (or `--quiet` to hide this note)."
+                .to_string(),
+        );
+    }
+
+    if options.day_gap == DayGap::Thin && !options.uses_gutters() {
+        notice(
+            quiet,
+            notices,
+            "warning: `--day-gap thin` is experimental, thin spaces may not line up in every \
+             terminal font."
+                .to_string(),
+        );
+    }
+
+    if options.fold_weekends && first_day_of_week == Weekday::Sun {
+        return Err(CalError::IncompatibleOptions(
+            "--fold-weekends-into-one-column needs weeks that start on Monday".to_string(),
+        ));
+    }
+
+    let holiday_sets = args.holiday_set.clone();
+
+    if let Some(date) = args.week_of {
+        let (start_date, end_date) = week_of_range(date, first_day_of_week)?;
+
+        // the week can start in the previous year or run into the next one
+        options.add_holidays(resolve_holiday_sets(
+            &holiday_sets,
+            start_date.year()..=end_date.year().saturating_add(1),
+        ));
+
+        return print_week_of(&options, date, first_day_of_week);
+    }
+
+    let date_ranges = determine_date_ranges(current_date, args)?;
+
+    if let (Some(&(start_date, _)), Some(&(_, end_date))) = (
+        date_ranges.iter().min_by_key(|(start_date, _)| *start_date),
+        date_ranges.iter().max_by_key(|(_, end_date)| *end_date),
+    ) {
+        // a Saturday New Year's Day can be observed on the last day of the previous year
+        options.add_holidays(resolve_holiday_sets(
+            &holiday_sets,
+            start_date.year()..=end_date.year().saturating_add(1),
+        ));
+    }
+
+    if let Some(max_months) = max_months {
+        let months = count_months(&date_ranges);
+
+        if months > max_months as usize {
+            return Err(CalError::TooManyMonths { months, max_months });
+        }
+    }
+
+    for (start_date, end_date) in &date_ranges {
+        info!("Printing calendar for {} - {}", start_date, end_date);
+    }
+
+    if let Some((parsed_input, resolved_input)) = explain {
+        // asked for explicitly, so it isn't hidden by `--quiet`
+        let explanation = explanation(
+            &parsed_input,
+            &resolved_input,
+            (first_day_of_week, first_day_of_week_source),
+            &date_ranges,
+        );
+        notices(explanation.trim_end().to_string());
+    }
+
+    let months = build_month_range_from_ranges(&date_ranges, first_day_of_week);
+    if months.months.is_empty() {
+        return Err(CalError::EmptyRange);
+    }
+
+    let months = if align_firsts {
+        MonthRange {
+            months: months
+                .months
+                .iter()
+                .filter_map(|month| build_month(month.days().collect(), month.start_date.weekday()))
+                .collect(),
+        }
+    } else {
+        months
+    };
+
+    if let Some(force_weeks) = options.force_weeks {
+        let hidden_days = months
+            .months
+            .iter()
+            .flat_map(|month| month.weeks.iter().skip(force_weeks))
+            .flat_map(|week| week.days(Weekday::Mon))
+            .count();
+
+        if hidden_days > 0 {
+            notice(
+                quiet,
+                notices,
+                format!(
+                    "warning: `--force-weeks {}` hides {} day(s) that don't fit in {} week(s).",
+                    force_weeks, hidden_days, force_weeks
+                ),
+            );
+        }
+    }
+
+    // the summary describes the dates requested, not the repeated copies
+    let summary = match fiscal_quarter_span(&options, &months) {
+        Some(quarters) => format!("{} ({})", months.summary(&options.holidays), quarters),
+        None => months.summary(&options.holidays).to_string(),
+    };
+    let legend = legend(&options, &months);
+
+    let months = if repeat > 1 {
+        MonthRange {
+            months: months
+                .months
+                .iter()
+                .flat_map(|month| {
+                    // each copy keeps its month's own first day of the week (see `--align-firsts`)
+                    (0..repeat).filter_map(|_| {
+                        build_month(month.days().collect(), month.first_day_of_week)
+                    })
+                })
+                .collect(),
+        }
+    } else {
+        months
+    };
+
+    if format == OutputFormat::Svg || format == OutputFormat::GridCsv {
+        if continuous {
+            return Err(CalError::UnsupportedFormat(format!(
+                "--continuous can't be printed as {}, only as text",
+                format.name()
+            )));
+        }
+
+        return Ok(match format {
+            OutputFormat::GridCsv => months.print_grid_csv(),
+            _ => months.print_svg(&options),
+        });
+    }
+
+    let mut output = String::new();
+
+    if is_diff {
+        let header = months
+            .months
+            .iter()
+            .map(|month| {
+                format!(
+                    "{} ({})",
+                    month.start_date.format("%B %Y"),
+                    month.start_date.format("%a")
+                )
+            })
+            .join(" vs ");
+        let width = options.columns * options.month_width() + 2;
+
+        output.push_str(&pad_to_width(&header, width, fmt::Alignment::Center));
+        output.push_str("\n\n");
+    }
+
+    if continuous {
+        output.push_str(&print_continuous(&options, &months, first_day_of_week));
+    } else {
+        output.push_str(&months.print(&options));
+    }
+
+    if let Some(legend) = legend {
+        output.push_str(&format!("\n{}", legend));
+    }
+
+    if range_summary {
+        output.push_str(&format!("\n{}\n", summary));
+    }
+
+    Ok(output)
+}
+
+/// Labels the fiscal quarters from the first through the last month displayed (e.g. `Q3 FY2024`,
+/// or `Q4 FY2024 - Q1 FY2025`), or `None` when fiscal years aren't in use.
+fn fiscal_quarter_span(options: &RenderOptions, months: &MonthRange) -> Option<String> {
+    options.fiscal_year_start?;
+
+    let first = options.quarter_label(months.months.first()?.start_date);
+    let last = options.quarter_label(months.months.last()?.start_date);
+
+    if first == last {
+        Some(first)
+    } else {
+        Some(format!("{} - {}", first, last))
+    }
+}
+
+/// Lists the holidays and labeled `--highlight-file` dates that fall within `months`, one per
+/// line, with each date styled the way it is in the grid.
+fn legend(options: &RenderOptions, months: &MonthRange) -> Option<String> {
+    let lines = months
+        .months
+        .iter()
+        .flat_map(|month| month.days())
+        .flat_map(|date| {
+            let holiday = options
+                .holidays
+                .get(&date)
+                .map(|name| (options.theme.holiday.clone(), name));
+            let highlight = options
+                .highlighted_dates
+                .get(&date)
+                .and_then(|highlighted| highlighted.label.as_ref())
+                .map(|label| (options.highlight_style_for(date), label));
+
+            holiday
+                .into_iter()
+                .chain(highlight)
+                .map(move |(style, name)| {
+                    let label = date.format("%Y-%m-%d").to_string();
+                    let label = if show_color(options.color) {
+                        style.paint(&label)
+                    } else {
+                        label
+                    };
+
+                    format!("{}  {}\n", label, name)
+                })
+        })
+        .collect::<String>();
+
+    (!lines.is_empty()).then_some(lines)
+}
+
+#[derive(Serialize)]
+struct WeekdayOf {
+    date: String,
+    weekday: String,
+    iso_weekday: u32,
+}
+
+fn print_weekday_of(date: NaiveDate, format: OutputFormat) -> Result<String, CalError> {
+    let weekday = date.format("%A").to_string();
+
+    match format {
+        OutputFormat::Text => Ok(weekday),
+        OutputFormat::Json => Ok(serde_json::to_string(&WeekdayOf {
+            date: date.format("%Y-%m-%d").to_string(),
+            weekday,
+            iso_weekday: date.weekday().number_from_monday(),
+        })
+        .expect("weekday information is always serializable")),
+        OutputFormat::Svg | OutputFormat::GridCsv => Err(CalError::UnsupportedFormat(format!(
+            "--weekday-of can't be printed as {}, only as text or json",
+            format.name()
+        ))),
+    }
+}
+
+#[derive(Serialize)]
+struct DateRange {
+    start: String,
+    end: String,
+}
+
+/// Prints each of `date_ranges` on its own line, either as `start..end` or as a JSON object.
+fn print_date_ranges(
+    date_ranges: &[(NaiveDate, NaiveDate)],
+    format: OutputFormat,
+) -> Result<String, CalError> {
+    let lines = date_ranges.iter().map(|(start, end)| match format {
+        OutputFormat::Text => Ok(format!("{}..{}", start, end)),
+        OutputFormat::Json => Ok(serde_json::to_string(&DateRange {
+            start: start.to_string(),
+            end: end.to_string(),
+        })
+        .expect("date ranges are always serializable")),
+        OutputFormat::Svg | OutputFormat::GridCsv => Err(CalError::UnsupportedFormat(format!(
+            "--print-range can't be printed as {}, only as text or json",
+            format.name()
+        ))),
+    });
+
+    Ok(lines.collect::<Result<Vec<_>, _>>()?.join("\n"))
+}
+
+/// Counts the days of each of `weekdays` within `date_ranges` (counting days covered by more than
+/// one range once). A single count is printed as a bare number, several are printed one per line
+/// (e.g. `Friday: 13`), or as a JSON object keyed by weekday.
+fn print_weekday_counts(
+    date_ranges: &[(NaiveDate, NaiveDate)],
+    weekdays: &[Weekday],
+    format: OutputFormat,
+) -> Result<String, CalError> {
+    let dates: BTreeSet<NaiveDate> = date_ranges
+        .iter()
+        .flat_map(|&(start_date, end_date)| date_range(start_date, end_date))
+        .collect();
+    let counts = weekdays.iter().map(|&weekday| {
+        let count = dates
+            .iter()
+            .filter(|date| date.weekday() == weekday)
+            .count();
+        let name = match weekday {
+            Weekday::Mon => "Monday",
+            Weekday::Tue => "Tuesday",
+            Weekday::Wed => "Wednesday",
+            Weekday::Thu => "Thursday",
+            Weekday::Fri => "Friday",
+            Weekday::Sat => "Saturday",
+            Weekday::Sun => "Sunday",
+        };
+
+        (name, count)
+    });
+
+    match format {
+        OutputFormat::Text if weekdays.len() == 1 => {
+            Ok(counts.map(|(_, count)| count.to_string()).collect())
+        }
+        OutputFormat::Text => Ok(counts
+            .map(|(name, count)| format!("{}: {}", name, count))
+            .join("\n")),
+        OutputFormat::Json => Ok(serde_json::Value::Object(
+            counts
+                .map(|(name, count)| (name.to_lowercase(), count.into()))
+                .collect(),
+        )
+        .to_string()),
+        format @ (OutputFormat::Svg | OutputFormat::GridCsv) => {
+            Err(CalError::UnsupportedFormat(format!(
+                "--count-weekday can't be printed as {}, only as text or json",
+                format.name()
+            )))
+        }
+    }
+}
+
+/// Lists the holidays in `set` for every year touched by `date_ranges`, one per line (e.g.
+/// `2024-07-04  Independence Day`), or as a JSON array.
+fn print_holiday_list(
+    set: HolidaySet,
+    date_ranges: &[(NaiveDate, NaiveDate)],
+    format: OutputFormat,
+) -> Result<String, CalError> {
+    let holidays = date_ranges
+        .iter()
+        .flat_map(|&(start_date, end_date)| start_date.year()..=end_date.year())
+        .unique()
+        .flat_map(|year| set.holidays(year))
+        .collect::<BTreeMap<_, _>>();
+
+    match format {
+        OutputFormat::Text => Ok(holidays
+            .iter()
+            .map(|(date, name)| format!("{}  {}", date, name))
+            .join("\n")),
+        OutputFormat::Json => Ok(serde_json::Value::Array(
+            holidays
+                .iter()
+                .map(|(date, name)| serde_json::json!({ "date": date.to_string(), "name": name }))
+                .collect(),
+        )
+        .to_string()),
+        format @ (OutputFormat::Svg | OutputFormat::GridCsv) => {
+            Err(CalError::UnsupportedFormat(format!(
+                "--list-holidays can't be printed as {}, only as text or json",
+                format.name()
+            )))
+        }
+    }
+}
+
+/// Writes the rendered `output` to `path`, followed by a newline (matching what is printed to
+/// stdout).
+pub fn write_output(path: &Path, output: &str) -> Result<(), CalError> {
+    std::fs::write(path, format!("{}\n", output)).map_err(|err| CalError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// Writes the rendered `output` to `writer` (i.e. stdout), followed by a newline. A reader that
+/// stops early (e.g. `cal 2024 -A 120 | head`) isn't an error, the rest of the output is just
+/// dropped.
+pub fn write_stdout(writer: &mut impl std::io::Write, output: &str) -> std::io::Result<()> {
+    match writeln!(writer, "{}", output).and_then(|()| writer.flush()) {
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Removes ANSI escape sequences (e.g. `\x1B[7m`), leaving only the visible text.
+fn strip_ansi(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1B' && chars.clone().next() == Some('[') {
+            // skip the parameters through the final byte of the control sequence
+            chars
+                .by_ref()
+                .skip(1)
+                .find(|c| ('\x40'..='\x7E').contains(c));
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Hashes the visible text of `output` with 64-bit FNV-1a, for `--verify`.
+fn output_hash(output: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let hash = strip_ansi(output).bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+
+    format!("{:016x}", hash)
+}
+
+/// Renders the week containing `date` as a weekday header followed by a single row of days. The
+/// week may include days from two different months.
+fn print_week_of(
+    options: &RenderOptions,
+    date: NaiveDate,
+    first_day_of_week: Weekday,
+) -> Result<String, CalError> {
+    let (start_date, end_date) = week_of_range(date, first_day_of_week)?;
+
+    let strip = Month {
+        start_date,
+        first_day_of_week,
+        weeks: build_weeks(date_range(start_date, end_date), first_day_of_week),
+    };
+    let mut output = String::new();
+
+    strip.print_weekday_header(options, &mut output);
+    output.push('\n');
+    if let Some(rule) = options.header_rule {
+        strip.print_header_rule(rule, options, &mut output);
+        output.push('\n');
+    }
+
+    for week in &strip.weeks {
+        week.print(options, first_day_of_week, &mut output);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Returns the first and last days of the week containing `date`.
+fn week_of_range(
+    date: NaiveDate,
+    first_day_of_week: Weekday,
+) -> Result<(NaiveDate, NaiveDate), CalError> {
+    let days_into_week =
+        (7 + date.weekday().num_days_from_monday() - first_day_of_week.num_days_from_monday()) % 7;
+    let out_of_range = || CalError::OutOfRange(format!("The week of {}", date));
+    let start_date = date
+        .checked_sub_days(chrono::Days::new(days_into_week.into()))
+        .ok_or_else(out_of_range)?;
+    let end_date = start_date
+        .checked_add_days(chrono::Days::new(6))
+        .ok_or_else(out_of_range)?;
+
+    Ok((start_date, end_date))
+}
+
+/// Prints every week of `months` as one list under a single weekday header, labeling the rows
+/// where a month starts.
+fn print_continuous(
+    options: &RenderOptions,
+    months: &MonthRange,
+    first_day_of_week: Weekday,
+) -> String {
+    let Some(first_day) = months.days().next() else {
+        return String::new();
+    };
+    let strip = Month {
+        start_date: first_day,
+        first_day_of_week,
+        weeks: build_weeks(months.days(), first_day_of_week),
+    };
+    // wide enough for labels like `Sep 2024`
+    let label_width = 8;
+    let mut output = String::new();
+
+    output.push_str(&" ".repeat(label_width + 1));
+    strip.print_weekday_header(options, &mut output);
+    output.push('\n');
+    if let Some(rule) = options.header_rule {
+        output.push_str(&" ".repeat(label_width + 1));
+        strip.print_header_rule(rule, options, &mut output);
+        output.push('\n');
+    }
+
+    for week in &strip.weeks {
+        let month_start = week
+            .days(first_day_of_week)
+            .find(|&day| day == first_day || day.day() == 1);
+        let label = match month_start {
+            Some(day) => day.format("%b %Y").to_string(),
+            None => String::new(),
+        };
+
+        output.push_str(&pad_to_width(&label, label_width, fmt::Alignment::Left));
+        output.push(' ');
+        week.print(options, first_day_of_week, &mut output);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use std::sync::{Mutex, MutexGuard};
+
+    // Tests that modify environment variables hold this lock so they don't race with each other.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    // The variables `ColorDepth::detect` reads, which `force_color` pins so that snapshots don't
+    // depend on the terminal the tests are run from.
+    const COLOR_DEPTH_VARS: [&str; 2] = ["COLORTERM", "TERM"];
+
+    struct ForceColor {
+        saved: Vec<(&'static str, Option<OsString>)>,
+        _guard: MutexGuard<'static, ()>,
+    }
+
+    impl Drop for ForceColor {
+        fn drop(&mut self) {
+            std::env::remove_var("FORCE_COLOR");
+
+            for (name, value) in &self.saved {
+                match value {
+                    Some(value) => std::env::set_var(name, value),
+                    None => std::env::remove_var(name),
+                }
+            }
+        }
+    }
+
+    /// Forces color on (`"1"`) or off (`"0"`) with the 256-color palette, until the returned
+    /// guard is dropped.
+    fn force_color(value: &str) -> ForceColor {
+        let guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let saved = COLOR_DEPTH_VARS
+            .map(|name| (name, std::env::var_os(name)))
+            .to_vec();
+
+        std::env::set_var("FORCE_COLOR", value);
+        std::env::remove_var("COLORTERM");
+        std::env::set_var("TERM", "xterm-256color");
+
+        ForceColor {
+            saved,
+            _guard: guard,
+        }
+    }
+
+    fn escape_ansi(s: &str) -> String {
+        s.replace('\x1B', "\\e")
+    }
+
+    // Wraps each line in `|` so that leading and trailing whitespace is visible in snapshots.
+    fn show_edges(s: &str) -> String {
+        s.lines().map(|line| format!("|{}|\n", line)).collect()
+    }
+
+    fn args<I, T>(itr: I) -> Arguments
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Arguments::parse_from(itr)
+    }
+
+    #[test]
+    fn test_parse_date_input_year() {
+        let style = YearStyle::Calendar;
+
+        assert_eq!(
+            parse_date_input("2024"),
+            Ok(DateInput::Year(Year { style, year: 2024 }))
+        );
+        assert_eq!(
+            parse_date_input("2000"),
+            Ok(DateInput::Year(Year { style, year: 2000 }))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_quarter() {
+        let style = YearStyle::Calendar;
+
+        assert_eq!(
+            parse_date_input("Q1"),
+            Ok(DateInput::CurrentYearQuarter(style, Quarter::Q1))
+        );
+        assert_eq!(
+            parse_date_input("Q2"),
+            Ok(DateInput::CurrentYearQuarter(style, Quarter::Q2))
+        );
+        assert_eq!(
+            parse_date_input("Q3"),
+            Ok(DateInput::CurrentYearQuarter(style, Quarter::Q3))
+        );
+        assert_eq!(
+            parse_date_input("Q4"),
+            Ok(DateInput::CurrentYearQuarter(style, Quarter::Q4))
+        );
+    }
+
+    #[test]
+    fn test_strict_date_input() {
+        let parse = |input: &str, strict: bool| {
+            let mut argv = vec!["cal", input];
+            if strict {
+                argv.push("--strict");
+            }
+
+            Arguments::try_parse_argv(
+                argv.into_iter().map(OsString::from).collect(),
+                &Config::default(),
+            )
+            .map(|args| args.date_input)
+        };
+
+        for canonical in [
+            "2024",
+            "2024-03",
+            "2024-Q1",
+            "FY2024",
+            "FY2024-Q1",
+            "Q1",
+            "FYQ2",
+            "+2",
+            "2024-06-01..2024-08-31",
+            "06-01..08-31",
+        ] {
+            assert_eq!(
+                parse(canonical, true).unwrap(),
+                parse(canonical, false).unwrap(),
+                "{}",
+                canonical
+            );
+        }
+
+        for lenient in [
+            "24",
+            "FY24",
+            "24Q1",
+            "q1",
+            "fy2024",
+            "fyq1",
+            "202401",
+            "2024Q1",
+            "FY2024Q1",
+            "2024/01",
+            "01/2024",
+            "2024-1",
+            "2024Q1-Q3",
+            "2024Q1+1",
+        ] {
+            assert!(parse(lenient, false).is_ok(), "{}", lenient);
+
+            let err = parse(lenient, true).unwrap_err().to_string();
+            assert!(err.contains("Non-canonical date"), "{}: {}", lenient, err);
+        }
+
+        // the flag is read from the parsed arguments, so it counts in either `--compat` mode and
+        // not after `--`
+        let err = Arguments::try_parse_argv(
+            ["cal", "--compat", "util-linux", "-s", "--strict", "24"]
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+            &Config::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Non-canonical date"), "{}", err);
+
+        let err = Arguments::try_parse_argv(
+            ["cal", "24", "--", "--strict"]
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+            &Config::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(!err.contains("Non-canonical date"), "{}", err);
+        assert!(err.contains("'--strict'"), "{}", err);
+
+        // other flags' values aren't affected
+        assert!(Arguments::try_parse_argv(
+            [
+                "cal",
+                "--strict",
+                "--start-date",
+                "2024/01",
+                "--num-months",
+                "2"
+            ]
+            .into_iter()
+            .map(OsString::from)
+            .collect(),
+            &Config::default()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_day_range() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            parse_date_input("2024-06-15..2024-08-01"),
+            Ok(DateInput::Range(date(2024, 6, 15), date(2024, 8, 1)))
+        );
+        assert_eq!(
+            parse_date_input("06-01..08-31"),
+            Ok(DateInput::CurrentYearRange((6, 1), (8, 31)))
+        );
+        assert_eq!(
+            parse_date_input("2024-08-01..2024-06-15"),
+            Err("Range ends before it starts: 2024-08-01..2024-06-15".to_string())
+        );
+        assert!(parse_date_input("06-01..08-32").is_err());
+        assert!(parse_date_input("6-1..8-31").is_err());
+        assert!(parse_date_input("2024-06-01..08-31").is_err());
+
+        let resolve = |current_date, s: &str| {
+            resolve_date_input(current_date, 1, None, parse_date_input(s).unwrap()).unwrap()
+        };
+
+        assert_eq!(
+            resolve(date(2023, 3, 20), "02-01..02-29"),
+            DateInput::Range(date(2023, 2, 1), date(2023, 2, 28))
+        );
+        assert_eq!(
+            resolve(date(2023, 3, 20), "11-15..01-15"),
+            DateInput::Range(date(2023, 11, 15), date(2024, 1, 15))
+        );
+
+        // a span that runs into the following year can't start in the last supported one
+        let last_year = NaiveDate::MAX.year();
+        assert_eq!(
+            resolve(date(last_year, 3, 20), "06-01..08-31"),
+            DateInput::Range(date(last_year, 6, 1), date(last_year, 8, 31))
+        );
+        assert_eq!(
+            resolve_date_input(
+                date(last_year, 12, 15),
+                1,
+                None,
+                parse_date_input("12-01..02-28").unwrap()
+            ),
+            Err(CalError::OutOfRange(format!("{}-02-28", last_year + 1)))
+        );
+    }
+
+    #[test]
+    fn test_quarter_offset() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 8, 20).unwrap();
+        let quarter = |style, year, quarter| DateInput::YearQuarter(Year { style, year }, quarter);
+        let resolve = |s: &str| {
+            resolve_date_input(current_date, 7, None, parse_date_input(s).unwrap()).unwrap()
+        };
+
+        assert_eq!(
+            parse_date_input("FY24Q2+1"),
+            Ok(DateInput::QuarterOffset(
+                Box::new(quarter(YearStyle::Fiscal, 24, Quarter::Q2)),
+                1
+            ))
+        );
+        assert_eq!(
+            resolve("FY24Q2+1"),
+            quarter(YearStyle::Fiscal, 2024, Quarter::Q3)
+        );
+        assert_eq!(
+            resolve("Q4+1"),
+            quarter(YearStyle::Calendar, 2025, Quarter::Q1)
+        );
+        assert_eq!(
+            resolve("FYQ1-1"),
+            quarter(YearStyle::Fiscal, 2024, Quarter::Q4)
+        );
+        assert_eq!(
+            resolve("2024Q1-1"),
+            quarter(YearStyle::Calendar, 2023, Quarter::Q4)
+        );
+        assert_eq!(
+            resolve("2024Q1+10"),
+            quarter(YearStyle::Calendar, 2026, Quarter::Q3)
+        );
+
+        // ranges and other formats with a dash are unaffected
+        assert_eq!(
+            parse_date_input("2024Q1-Q3"),
+            Ok(DateInput::YearQuarterRange(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: 2024
+                },
+                Quarter::Q1,
+                Quarter::Q3
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY2024-Q1"),
+            Ok(quarter(YearStyle::Fiscal, 2024, Quarter::Q1))
+        );
+        assert!(parse_date_input("2024Q1-Q3+1").is_err());
+        assert!(parse_date_input("2024Q1+1000").is_err());
+
+        // shifting the largest years doesn't overflow, years past chrono's range are reported when
+        // the date range is determined
+        assert_eq!(
+            resolve("FY999999999Q1+1"),
+            quarter(YearStyle::Fiscal, 999999999, Quarter::Q2)
+        );
+        assert_eq!(
+            resolve_date_input(
+                current_date,
+                7,
+                None,
+                parse_date_input(&format!("FY{}Q4+1", i32::MAX)).unwrap()
+            ),
+            Err(CalError::OutOfRange(format!(
+                "FY{}Q1",
+                i64::from(i32::MAX) + 1
+            )))
+        );
+        assert_eq!(
+            resolve_date_input(
+                current_date,
+                7,
+                None,
+                parse_date_input(&format!("FY{}Q1-1", i32::MAX)).unwrap()
+            ),
+            Ok(quarter(YearStyle::Fiscal, i32::MAX - 1, Quarter::Q4))
+        );
+        assert!(matches!(
+            determine_date_ranges(
+                current_date,
+                Arguments {
+                    date_input: vec![resolve("FY999999999Q1+1")],
+                    ..args(["cal", "--fiscal"])
+                }
+            ),
+            Err(CalError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_date_input_relative_month() {
+        assert_eq!(parse_date_input("+2"), Ok(DateInput::RelativeMonth(2)));
+        assert_eq!(parse_date_input("-1"), Ok(DateInput::RelativeMonth(-1)));
+        assert_eq!(parse_date_input("+120"), Ok(DateInput::RelativeMonth(120)));
+        assert_eq!(parse_date_input("-0"), Ok(DateInput::RelativeMonth(0)));
+        assert!(parse_date_input("+1200").is_err());
+        assert!(parse_date_input("+").is_err());
+        assert!(parse_date_input("+2x").is_err());
+
+        // unsigned numbers are still years
+        assert!(matches!(parse_date_input("24"), Ok(DateInput::Year(_))));
+    }
+
+    #[test]
+    fn test_resolve_date_input_relative_month() {
+        let month = |year, month| {
+            DateInput::YearMonth(
+                Year {
+                    style: YearStyle::Calendar,
+                    year,
+                },
+                month,
+            )
+        };
+        let resolve = |current_date, offset| {
+            resolve_date_input(current_date, 7, None, DateInput::RelativeMonth(offset)).unwrap()
+        };
+        let november = NaiveDate::from_ymd_opt(2024, 11, 20).unwrap();
+        let january = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+
+        assert_eq!(resolve(november, 0), month(2024, 11));
+        assert_eq!(resolve(november, 1), month(2024, 12));
+        assert_eq!(resolve(november, 2), month(2025, 1));
+        assert_eq!(resolve(november, 26), month(2027, 1));
+        assert_eq!(resolve(january, -1), month(2024, 12));
+        assert_eq!(resolve(january, -13), month(2023, 12));
+        assert_eq!(
+            resolve_date_input(NaiveDate::MAX, 7, None, DateInput::RelativeMonth(1)),
+            Err(CalError::OutOfRange(format!(
+                "{}-01",
+                NaiveDate::MAX.year() + 1
+            )))
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_relative_month() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 12, 15).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        // relative months are resolved by `print` before the ranges are determined
+        let print_ranges = |current_date, argv: &[&str]| {
+            let args = args(argv);
+            let date_input = args
+                .date_input
+                .iter()
+                .map(|date_input| {
+                    resolve_date_input(current_date, 7, None, date_input.clone()).unwrap()
+                })
+                .collect();
+
+            determine_date_ranges(current_date, Arguments { date_input, ..args }).unwrap()
+        };
+
+        assert_eq!(
+            print_ranges(current_date, &["cal", "+2"]),
+            vec![(date(2025, 2, 1), date(2025, 2, 28))]
+        );
+        assert_eq!(
+            print_ranges(current_date, &["cal", "--", "-2"]),
+            vec![(date(2024, 10, 1), date(2024, 10, 31))]
+        );
+        assert_eq!(
+            print_ranges(current_date, &["cal", "--", "-1"]),
+            vec![(date(2024, 11, 1), date(2024, 11, 30))]
+        );
+
+        assert_eq!(
+            args(["cal", "--", "-12", "+2"]).date_input,
+            vec![DateInput::RelativeMonth(-12), DateInput::RelativeMonth(2)]
+        );
+        // without `--`, `-1` and `-3` are still `--one` and `--three`
+        assert!(args(["cal", "-1"]).one);
+        assert!(args(["cal", "-3"]).three);
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--", "-1"])),
+            Err(CalError::InvalidDateInput(
+                "Relative months must be resolved before determining the date range".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_normalize_short_year_pivot() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        assert_eq!(normalize_short_year(current_date, None, 98), 2098);
+        assert_eq!(normalize_short_year(current_date, Some(50), 49), 2049);
+        assert_eq!(normalize_short_year(current_date, Some(50), 50), 1950);
+        assert_eq!(normalize_short_year(current_date, Some(50), 98), 1998);
+        assert_eq!(normalize_short_year(current_date, Some(0), 0), 1900);
+        assert_eq!(normalize_short_year(current_date, Some(99), 98), 2098);
+        assert_eq!(normalize_short_year(current_date, Some(99), 99), 1999);
+        assert_eq!(normalize_short_year(current_date, Some(50), 1998), 1998);
+
+        assert_eq!(
+            print(
+                args(["cal", "--print-range", "--year-pivot", "50", "98"]),
+                current_date
+            ),
+            Ok("1998-01-01..1998-12-31".to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--print-range", "--year-pivot", "50", "FY24Q1"]),
+                current_date
+            ),
+            Ok("2023-07-01..2023-09-30".to_string())
+        );
+        assert!(Arguments::try_parse_from(["cal", "--year-pivot", "100"]).is_err());
+    }
+
+    #[test]
+    fn test_resolve_date_input_current_year_quarter() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 8, 20).unwrap();
+        let calendar = YearStyle::Calendar;
+        let fiscal = YearStyle::Fiscal;
+
+        assert_eq!(
+            resolve_date_input(
+                current_date,
+                7,
+                None,
+                DateInput::CurrentYearQuarter(calendar, Quarter::Q1)
+            )
+            .unwrap(),
+            DateInput::YearQuarter(
+                Year {
+                    style: calendar,
+                    year: 2024
+                },
+                Quarter::Q1
+            )
+        );
+        assert_eq!(
+            resolve_date_input(
+                current_date,
+                7,
+                None,
+                DateInput::CurrentYearQuarter(fiscal, Quarter::Q1)
+            )
+            .unwrap(),
+            DateInput::YearQuarter(
+                Year {
+                    style: fiscal,
+                    year: 2025
+                },
+                Quarter::Q1
+            )
+        );
+        assert_eq!(
+            resolve_date_input(
+                current_date,
+                10,
+                None,
+                DateInput::CurrentYearQuarter(fiscal, Quarter::Q1)
+            )
+            .unwrap(),
+            DateInput::YearQuarter(
+                Year {
+                    style: fiscal,
+                    year: 2024
+                },
+                Quarter::Q1
+            )
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_fiscal_year_flag() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--fiscal", "--year", "2024"]))
+                .unwrap(),
+            vec![(date(2023, 7, 1), date(2024, 6, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args([
+                    "cal",
+                    "--fiscal",
+                    "--year",
+                    "2024",
+                    "--fiscal-year-start",
+                    "10"
+                ])
+            )
+            .unwrap(),
+            vec![(date(2023, 10, 1), date(2024, 9, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args([
+                    "cal",
+                    "--fiscal",
+                    "--year",
+                    "2024",
+                    "--fiscal-year-start",
+                    "1"
+                ])
+            )
+            .unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 12, 31))]
+        );
+        // an explicit month is always a calendar month
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--fiscal", "--year", "2024", "--month", "3"])
+            )
+            .unwrap(),
+            vec![(date(2024, 3, 1), date(2024, 3, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--fiscal", "--year=-262143"])),
+            Err(CalError::OutOfRange("-262143".to_string()))
+        );
+
+        // the fiscal year starts in range, but ends after the last representable month
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--fiscal", "--year", "262143"])),
+            Err(CalError::OutOfRange("262143-06".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "FY262143"])),
+            Err(CalError::OutOfRange("262143-06".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_fiscal_year() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "FY2024"])).unwrap(),
+            vec![(date(2023, 7, 1), date(2024, 6, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "FY2024", "--fiscal-year-start", "10"])
+            )
+            .unwrap(),
+            vec![(date(2023, 10, 1), date(2024, 9, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "FY2024", "--fiscal-year-start", "1"])
+            )
+            .unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 12, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "FY2024Q2", "--fiscal-year-start", "10"])
+            )
+            .unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 3, 31))]
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_fiscal_year() {
+        let style = YearStyle::Fiscal;
+
+        assert_eq!(
+            parse_date_input("FY2024"),
+            Ok(DateInput::Year(Year { style, year: 2024 }))
+        );
+        assert_eq!(
+            parse_date_input("FY1900"),
+            Ok(DateInput::Year(Year { style, year: 1900 }))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_year_quarter() {
+        let style = YearStyle::Calendar;
+
+        assert_eq!(
+            parse_date_input("2024Q1"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2024 },
+                Quarter::Q1
+            ))
+        );
+        assert_eq!(
+            parse_date_input("2000Q3"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2000 },
+                Quarter::Q3
+            ))
+        );
+        assert_eq!(
+            parse_date_input("1900Q2"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 1900 },
+                Quarter::Q2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_fiscal_quarter() {
+        let style = YearStyle::Fiscal;
+
+        assert_eq!(
+            parse_date_input("FY2024Q1"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2024 },
+                Quarter::Q1
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY2000Q2"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2000 },
+                Quarter::Q2
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY1900Q3"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 1900 },
+                Quarter::Q3
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY2024Q4"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2024 },
+                Quarter::Q4
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY2024-Q1"),
+            Ok(DateInput::YearQuarter(
+                Year { style, year: 2024 },
+                Quarter::Q1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_quarter_range() {
+        assert_eq!(
+            parse_date_input("2024Q1-Q3"),
+            Ok(DateInput::YearQuarterRange(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: 2024
+                },
+                Quarter::Q1,
+                Quarter::Q3
+            ))
+        );
+        assert_eq!(
+            parse_date_input("FY2024-Q2-Q4"),
+            Ok(DateInput::YearQuarterRange(
+                Year {
+                    style: YearStyle::Fiscal,
+                    year: 2024
+                },
+                Quarter::Q2,
+                Quarter::Q4
+            ))
+        );
+        assert_eq!(
+            parse_date_input("2024Q3-Q1"),
+            Err("Invalid quarter range (end is before start): 2024Q3-Q1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_quarter_range() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "2024Q1-Q3"])).unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 9, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "FY2024Q2-Q4"])).unwrap(),
+            vec![(date(2023, 10, 1), date(2024, 6, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "2024Q2-Q2"])).unwrap(),
+            vec![(date(2024, 4, 1), date(2024, 6, 30))]
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_year_month() {
+        let style = YearStyle::Calendar;
+
+        assert_eq!(
+            parse_date_input("2024-01"),
+            Ok(DateInput::YearMonth(Year { style, year: 2024 }, 1))
+        );
+        assert_eq!(
+            parse_date_input("202401"),
+            Ok(DateInput::YearMonth(Year { style, year: 2024 }, 1))
+        );
+        assert_eq!(
+            parse_date_input("2000-06"),
+            Ok(DateInput::YearMonth(Year { style, year: 2000 }, 6))
+        );
+        assert_eq!(
+            parse_date_input("200006"),
+            Ok(DateInput::YearMonth(Year { style, year: 2000 }, 6))
+        );
+        assert_eq!(
+            parse_date_input("1900-12"),
+            Ok(DateInput::YearMonth(Year { style, year: 1900 }, 12))
+        );
+        assert_eq!(
+            parse_date_input("190012"),
+            Ok(DateInput::YearMonth(Year { style, year: 1900 }, 12))
+        );
+    }
+
+    #[test]
+    fn test_parse_date_input_slash_separated() {
+        let style = YearStyle::Calendar;
+
+        assert_eq!(
+            parse_date_input("2024/03"),
+            Ok(DateInput::YearMonth(Year { style, year: 2024 }, 3))
+        );
+        assert_eq!(
+            parse_date_input("03/2024"),
+            Ok(DateInput::YearMonth(Year { style, year: 2024 }, 3))
+        );
+        assert_eq!(
+            parse_date_input("3/2024"),
+            Ok(DateInput::YearMonth(Year { style, year: 2024 }, 3))
+        );
+        assert_eq!(
+            parse_date_input("03/04"),
+            Err("Ambiguous date format: 03/04 (use YYYY-MM, e.g. 2024-03)".to_string())
+        );
+        assert_eq!(
+            parse_date_input("2024/13"),
+            Err("Invalid month detected (must be 1 - 12): 13".to_string())
+        );
+    }
+
+    #[test]
+    fn test_today_in_timezone() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 20, 2, 0, 0).unwrap();
+
+        assert_eq!(
+            today_in(Some(chrono_tz::America::Los_Angeles), now),
+            NaiveDate::from_ymd_opt(2024, 3, 19).unwrap()
+        );
+        assert_eq!(
+            today_in(Some(chrono_tz::Asia::Tokyo), now),
+            NaiveDate::from_ymd_opt(2024, 3, 20).unwrap()
+        );
+        assert_eq!(
+            parse_timezone("Europe/London"),
+            Ok(chrono_tz::Europe::London)
+        );
+        assert_eq!(
+            parse_timezone("Mars/Olympus_Mons"),
+            Err("Unknown time zone: Mars/Olympus_Mons (expected an IANA name like America/New_York)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_show_color_precedence() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let config = Config::parse("color = \"always\"").unwrap();
+        let color = |argv: &[&str]| {
+            let args = Arguments::parse_from(argv).with_config(&config);
+
+            show_color(args.color.unwrap_or(ColorWhen::Auto))
+        };
+
+        // tests don't run in a terminal, so auto means no color
+        assert!(!show_color(ColorWhen::Auto));
+        assert!(color(&["cal"]));
+        assert!(!color(&["cal", "--color=never"]));
+        assert!(!color(&["cal", "--color=auto"]));
+
+        std::env::set_var("NO_COLOR", "1");
+        assert!(!color(&["cal"]));
+        assert!(!color(&["cal", "--color=always"]));
+
+        std::env::set_var("FORCE_COLOR", "1");
+        assert!(color(&["cal", "--color=never"]));
+
+        std::env::set_var("FORCE_COLOR", "0");
+        std::env::set_var("NO_COLOR", "");
+        assert!(!color(&["cal", "--color=always"]));
+
+        std::env::remove_var("FORCE_COLOR");
+        assert!(color(&["cal"]));
+
+        std::env::remove_var("NO_COLOR");
+        assert!(Config::parse("color = \"sometimes\"").is_err());
+    }
+
+    #[test]
+    fn test_resolve_color() {
+        let file = Some(Path::new("calendar.txt"));
+
+        assert_eq!(resolve_color(None, None, true), ColorWhen::Always);
+        assert_eq!(resolve_color(None, None, false), ColorWhen::Never);
+        assert_eq!(
+            resolve_color(Some(ColorWhen::Never), None, true),
+            ColorWhen::Never
+        );
+
+        // a file never counts as a terminal, even when stdout is one
+        assert_eq!(resolve_color(None, file, true), ColorWhen::Never);
+        assert_eq!(
+            resolve_color(Some(ColorWhen::Auto), file, true),
+            ColorWhen::Never
+        );
+        assert_eq!(
+            resolve_color(Some(ColorWhen::Always), file, false),
+            ColorWhen::Always
+        );
+    }
+
+    #[test]
+    fn test_print_output_file_color() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        std::env::remove_var("FORCE_COLOR");
+        std::env::remove_var("NO_COLOR");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let print_args = |argv: &[&str]| print(args(argv.iter().copied()), current_date).unwrap();
+
+        for color in ["--color=auto", "--color=never"] {
+            let output = print_args(&["cal", "-f", "sunday", "--output", "cal.txt", color]);
+            assert!(!output.contains('\x1B'), "{}", color);
+        }
+        assert!(
+            print_args(&["cal", "-f", "sunday", "--output", "cal.txt", "--color"]).contains('\x1B')
+        );
+    }
+
+    #[test]
+    fn test_date_input_from_env() {
+        let _color = force_color("0");
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let ranges = |argv: &[&str]| {
+            determine_date_ranges(current_date, args(argv).with_env().unwrap()).unwrap()
+        };
+
+        std::env::set_var("CAL_DATE", "2024-07");
+        insta::assert_snapshot!(
+            print(args(["cal", "-f", "sunday"]).with_env().unwrap(), current_date).unwrap(),
+            @r###"
+             July 2024      
+        Su Mo Tu We Th Fr Sa
+            1  2  3  4  5  6
+         7  8  9 10 11 12 13
+        14 15 16 17 18 19 20
+        21 22 23 24 25 26 27
+        28 29 30 31         
+        "###
+        );
+        assert_eq!(
+            ranges(&["cal", "-A", "1"]),
+            vec![(date(2024, 7, 1), date(2024, 8, 31))]
+        );
+
+        // anything on the command line that picks the dates takes precedence
+        assert_eq!(
+            ranges(&["cal", "2024-05"]),
+            vec![(date(2024, 5, 1), date(2024, 5, 31))]
+        );
+        assert_eq!(
+            ranges(&["cal", "--year", "2023", "--month", "2"]),
+            vec![(date(2023, 2, 1), date(2023, 2, 28))]
+        );
+
+        std::env::set_var("CAL_DATE", "24");
+        assert!(args(["cal"]).with_env().is_ok());
+        assert_eq!(
+            args(["cal", "--strict"]).with_env().unwrap_err(),
+            "invalid CAL_DATE: Non-canonical date: 24 (see `--strict` in --help for the accepted forms)"
+        );
+
+        std::env::remove_var("CAL_DATE");
+        assert!(args(["cal"]).with_env().unwrap().date_input.is_empty());
+    }
+
+    #[test]
+    fn test_today_override() {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+
+        std::env::set_var("CAL_TODAY", "2024-02-29");
+        assert_eq!(today(None), date);
+        assert_eq!(today(Some(chrono_tz::Asia::Tokyo)), date);
+        assert_eq!(args(["cal", "--timezone", "Asia/Tokyo"]).today(), date);
+        assert_eq!(RenderOptions::default().current_date, date);
+
+        std::env::set_var("CAL_TODAY", "tomorrow");
+        assert_eq!(today(None), today_in(None, Utc::now()));
+
+        std::env::remove_var("CAL_TODAY");
+    }
+
+    #[test]
+    fn test_today_in_across_dst_transitions() {
+        let new_york = Some(chrono_tz::America::New_York);
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        // clocks spring forward at 2am EST (07:00 UTC) on 2024-03-10
+        for (hour, minute) in [(5, 0), (6, 59), (7, 0), (12, 0), (23, 59)] {
+            let now = Utc.with_ymd_and_hms(2024, 3, 10, hour, minute, 0).unwrap();
+            assert_eq!(today_in(new_york, now), date(2024, 3, 10), "{}", now);
+        }
+        // the day ends at midnight EDT (04:00 UTC), an hour earlier in UTC than it started
+        let now = Utc.with_ymd_and_hms(2024, 3, 11, 3, 59, 0).unwrap();
+        assert_eq!(today_in(new_york, now), date(2024, 3, 10));
+        let now = Utc.with_ymd_and_hms(2024, 3, 11, 4, 0, 0).unwrap();
+        assert_eq!(today_in(new_york, now), date(2024, 3, 11));
+
+        // clocks fall back at 2am EDT (06:00 UTC) on 2024-11-03, making the day 25 hours long
+        for hour in [4, 5, 6, 7, 23] {
+            let now = Utc.with_ymd_and_hms(2024, 11, 3, hour, 0, 0).unwrap();
+            assert_eq!(today_in(new_york, now), date(2024, 11, 3), "{}", now);
+        }
+        let now = Utc.with_ymd_and_hms(2024, 11, 4, 4, 59, 0).unwrap();
+        assert_eq!(today_in(new_york, now), date(2024, 11, 3));
+
+        // which day is today determines the week it is displayed in, not the time of day
+        let current_date = today_in(
+            new_york,
+            Utc.with_ymd_and_hms(2024, 3, 10, 7, 0, 0).unwrap(),
+        );
+        let months = MonthRange::new(
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            Weekday::Sun,
+        );
+        assert!(months.months[0].weeks[2].contains(current_date));
+        assert_eq!(current_date.weekday(), Weekday::Sun);
+    }
+
+    #[test]
+    fn test_parse_date_input_invalid() {
+        assert!(parse_date_input("").is_err());
+        assert!(parse_date_input("invalid").is_err());
+        assert!(parse_date_input("2024-13").is_err());
+        assert!(parse_date_input("FY").is_err());
+        assert!(parse_date_input("Q5").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_input_invalid_year_length() {
+        assert_eq!(
+            parse_date_input("202"),
+            Err("Invalid year: 202 (3-digit years are not supported; use a 4-digit year or 2-digit shorthand)".to_string())
+        );
+        assert_eq!(
+            parse_date_input("99999"),
+            Err("Invalid date: 99999 (use a 4-digit year like 2024 or a year and month like 202403)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_month_contains() {
+        let month = build_month_range(
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+            Weekday::Mon,
+        )
+        .months
+        .remove(0);
+
+        assert!(month.contains(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()));
+        assert!(month.contains(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        assert!(!month.contains(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()));
+        assert!(!month.contains(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()));
+        assert!(!month.contains(NaiveDate::from_ymd_opt(2023, 2, 15).unwrap()));
+    }
+
+    #[test]
+    fn test_month_range_contains() {
+        let months = build_month_range(
+            NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            Weekday::Sun,
+        );
+
+        assert!(months.contains(NaiveDate::from_ymd_opt(2023, 12, 1).unwrap()));
+        assert!(months.contains(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()));
+        assert!(months.contains(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()));
+        assert!(months.contains(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()));
+        assert!(!months.contains(NaiveDate::from_ymd_opt(2023, 11, 30).unwrap()));
+        assert!(!months.contains(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()));
+    }
+
+    #[test]
+    fn test_month_range_date_range() {
+        let months = build_month_range(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            Weekday::Mon,
+        );
+
+        assert_eq!(
+            months.date_range(),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_month_range_days() {
+        let months = build_month_range(
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            Weekday::Sun,
+        );
+
+        let days: Vec<NaiveDate> = months.days().collect();
+        let expected: Vec<NaiveDate> = date_range(
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+        )
+        .collect();
+
+        assert_eq!(days.len(), 60);
+        assert_eq!(days, expected);
+    }
+
+    #[test]
+    fn test_date_range_bounds() {
+        let days: Vec<NaiveDate> =
+            date_range(NaiveDate::MAX.pred_opt().unwrap(), NaiveDate::MAX).collect();
+        assert_eq!(
+            days,
+            vec![NaiveDate::MAX.pred_opt().unwrap(), NaiveDate::MAX]
+        );
+
+        assert_eq!(date_range(NaiveDate::MIN, NaiveDate::MIN).count(), 1);
+    }
+
+    #[test]
+    fn test_print_year_bounds() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        insta::assert_snapshot!(
+            print(args(["cal", "-f", "monday", "-y", "1", "-m", "1"]), current_date).unwrap(),
+            @r###"
+            January 0001    
+        Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7
+         8  9 10 11 12 13 14
+        15 16 17 18 19 20 21
+        22 23 24 25 26 27 28
+        29 30 31            
+        "###
+        );
+
+        // the first and last weeks that chrono supports start and end outside of its range
+        for first_day_of_week in ["monday", "sunday"] {
+            for year in ["-262143", "262142"] {
+                let output = print(
+                    args([
+                        "cal",
+                        "-f",
+                        first_day_of_week,
+                        &format!("--year={}", year),
+                        "--trailing-days-from-adjacent-months",
+                        "--highlight-current-week",
+                    ]),
+                    current_date,
+                );
+
+                assert!(output.is_ok(), "{} {}", first_day_of_week, year);
+            }
+        }
+    }
+
+    #[test]
+    fn test_month_range_days_is_lazy() {
+        let months = build_month_range(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+            Weekday::Mon,
+        );
+
+        let mut days = months.days().skip(30);
+
+        assert_eq!(days.next(), NaiveDate::from_ymd_opt(2024, 1, 31));
+        assert_eq!(days.next(), NaiveDate::from_ymd_opt(2024, 2, 1));
+    }
+
+    #[test]
+    fn test_determine_default_first_day_of_week_argument() {
+        assert_eq!(
+            determine_default_first_day_of_week(Some(FirstDayOfWeek::Sunday)),
+            (Weekday::Sun, FirstDayOfWeekSource::Argument)
+        );
+    }
+
+    #[test]
+    fn test_first_day_of_week_from_str() {
+        assert_eq!("Mon".parse(), Ok(FirstDayOfWeek::Monday));
+        assert_eq!("MONDAY".parse(), Ok(FirstDayOfWeek::Monday));
+        assert_eq!("mo".parse(), Ok(FirstDayOfWeek::Monday));
+        assert_eq!("sunday".parse(), Ok(FirstDayOfWeek::Sunday));
+        assert_eq!("Sun".parse(), Ok(FirstDayOfWeek::Sunday));
+        assert_eq!(
+            "tuesday".parse::<FirstDayOfWeek>(),
+            Err("Invalid first day of week: tuesday (expected sunday or monday)".to_string())
+        );
+        assert!("".parse::<FirstDayOfWeek>().is_err());
+    }
+
+    #[test]
+    fn test_parse_week_start_offset() {
+        assert_eq!(parse_week_start_offset("0"), Ok(FirstDayOfWeek::Monday));
+        for offset in ["1", "2", "3", "4", "5"] {
+            assert_eq!(
+                parse_week_start_offset(offset),
+                Err(format!(
+                    "Unsupported week start offset (only 0 for Monday and 6 for Sunday are \
+                     supported): {}",
+                    offset
+                ))
+            );
+        }
+        assert_eq!(parse_week_start_offset("6"), Ok(FirstDayOfWeek::Sunday));
+
+        for offset in ["7", "-1", "monday", ""] {
+            assert_eq!(
+                parse_week_start_offset(offset),
+                Err(format!(
+                    "Invalid week start offset (expected 0 - 6, counting from Monday): {}",
+                    offset
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_print_week_start_offset() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let print_args = |argv: &[&str]| print(args(argv.iter().copied()), current_date).unwrap();
+
+        assert_eq!(
+            print_args(&["cal", "--week-start-offset", "0"]),
+            print_args(&["cal", "-f", "monday"])
+        );
+        assert_eq!(
+            print_args(&["cal", "--week-start-offset", "6"]),
+            print_args(&["cal", "-f", "sunday"])
+        );
+        assert!(
+            Arguments::try_parse_from(["cal", "--week-start-offset", "6", "-f", "sunday"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_first_day_of_week_note_does_not_affect_stdout() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let output = print(args(["cal"]), current_date).unwrap();
+
+        assert!(!output.contains("note:"));
+        assert_eq!(
+            output,
+            print(args(["cal", "--quiet"]), current_date).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_weeks_across_year_boundary() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        let weeks = build_weeks(
+            date_range(date(2024, 12, 29), date(2025, 1, 4)),
+            Weekday::Sun,
+        );
+
+        assert_eq!(weeks.len(), 1);
+        assert_eq!(
+            weeks[0].days(Weekday::Sun).collect::<Vec<_>>(),
+            date_range(date(2024, 12, 29), date(2025, 1, 4)).collect::<Vec<_>>()
+        );
+
+        let weeks = build_weeks(
+            date_range(date(2024, 12, 30), date(2025, 1, 12)),
+            Weekday::Mon,
+        );
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].day(Weekday::Mon), Some(date(2024, 12, 30)));
+        assert_eq!(weeks[0].day(Weekday::Sun), Some(date(2025, 1, 5)));
+        assert_eq!(weeks[1].day(Weekday::Mon), Some(date(2025, 1, 6)));
+    }
+
+    #[test]
+    fn test_week_from_dates() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        // spans the end of February and the start of March
+        let week = Week::from_dates(
+            &date_range(date(2024, 2, 25), date(2024, 3, 2)).collect_vec(),
+            Weekday::Sun,
+        )
+        .unwrap();
+
+        assert_eq!(
+            week.days(Weekday::Sun).collect_vec(),
+            date_range(date(2024, 2, 25), date(2024, 3, 2)).collect_vec()
+        );
+
+        // gaps leave their slots empty, and the order of the dates doesn't matter
+        let week = Week::from_dates(&[date(2024, 3, 1), date(2024, 2, 26)], Weekday::Mon).unwrap();
+
+        assert_eq!(week.day(Weekday::Mon), Some(date(2024, 2, 26)));
+        assert_eq!(week.day(Weekday::Tue), None);
+        assert_eq!(week.day(Weekday::Fri), Some(date(2024, 3, 1)));
+        assert_eq!(week.first_day(), Some(date(2024, 2, 26)));
+
+        // Sunday the 3rd starts a new week when weeks start on Sunday, but not on Monday
+        let dates = [date(2024, 3, 2), date(2024, 3, 3)];
+        assert!(Week::from_dates(&dates, Weekday::Sun).is_none());
+        assert!(Week::from_dates(&dates, Weekday::Mon).is_some());
+        assert!(Week::from_dates(&[], Weekday::Mon).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_week_metadata() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        // March 2024 starts on a Friday, so a Sunday-first week has five leading empty slots
+        let weeks = build_weeks(
+            date_range(date(2024, 3, 1), date(2024, 3, 31)),
+            Weekday::Sun,
+        );
+
+        assert_eq!(weeks[0].first_day(), Some(date(2024, 3, 1)));
+        assert_eq!(weeks[0].iso_week_number(), Some(9));
+        // Sunday the 3rd is the last day of ISO week 9, the rest of the row is week 10
+        assert_eq!(weeks[1].first_day(), Some(date(2024, 3, 3)));
+        assert_eq!(weeks[1].iso_week_number(), Some(9));
+        assert_eq!(
+            weeks[0].days(Weekday::Sun).collect::<Vec<_>>(),
+            vec![date(2024, 3, 1), date(2024, 3, 2)]
+        );
+
+        // the first days of January can belong to the last ISO week of the previous year
+        let weeks = build_weeks(
+            date_range(date(2021, 1, 1), date(2021, 1, 31)),
+            Weekday::Mon,
+        );
+
+        assert_eq!(weeks[0].first_day(), Some(date(2021, 1, 1)));
+        assert_eq!(weeks[0].iso_week_number(), Some(53));
+        assert_eq!(weeks[1].iso_week_number(), Some(1));
+
+        assert_eq!(Week::new().first_day(), None);
+        assert_eq!(Week::new().iso_week_number(), None);
+    }
+
+    #[test]
+    fn test_month_print_simple() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             March 2024     
+        Mo Tu We Th Fr Sa Su
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_quarter() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "Q1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_quarter_lowercase() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "q1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_fiscal_quarter() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "FYQ3"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_fiscal_quarter_lowercase() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "fyq3"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_year() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "2024"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+                                                                        
+
+             April 2024             May 2024             June 2024      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7         1  2  3  4  5                  1  2
+         8  9 10 11 12 13 14   6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        15 16 17 18 19 20 21  13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        22 23 24 25 26 27 28  20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        29 30                 27 28 29 30 31        24 25 26 27 28 29 30
+                                                                        
+
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+
+            October 2024         November 2024         December 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+            1  2  3  4  5  6               1  2  3                     1
+         7  8  9 10 11 12 13   4  5  6  7  8  9 10   2  3  4  5  6  7  8
+        14 15 16 17 18 19 20  11 12 13 14 15 16 17   9 10 11 12 13 14 15
+        21 22 23 24 25 26 27  18 19 20 21 22 23 24  16 17 18 19 20 21 22
+        28 29 30 31           25 26 27 28 29 30     23 24 25 26 27 28 29
+                                                    30 31               
+        "###);
+    }
+
+    #[test]
+    fn test_print_fiscal_year() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "FY2025"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+
+            October 2024         November 2024         December 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+            1  2  3  4  5  6               1  2  3                     1
+         7  8  9 10 11 12 13   4  5  6  7  8  9 10   2  3  4  5  6  7  8
+        14 15 16 17 18 19 20  11 12 13 14 15 16 17   9 10 11 12 13 14 15
+        21 22 23 24 25 26 27  18 19 20 21 22 23 24  16 17 18 19 20 21 22
+        28 29 30 31           25 26 27 28 29 30     23 24 25 26 27 28 29
+                                                    30 31               
+
+            January 2025         February 2025           March 2025     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+               1  2  3  4  5                  1  2                  1  2
+         6  7  8  9 10 11 12   3  4  5  6  7  8  9   3  4  5  6  7  8  9
+        13 14 15 16 17 18 19  10 11 12 13 14 15 16  10 11 12 13 14 15 16
+        20 21 22 23 24 25 26  17 18 19 20 21 22 23  17 18 19 20 21 22 23
+        27 28 29 30 31        24 25 26 27 28        24 25 26 27 28 29 30
+                                                    31                  
+
+             April 2025             May 2025             June 2025      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+            1  2  3  4  5  6            1  2  3  4                     1
+         7  8  9 10 11 12 13   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        14 15 16 17 18 19 20  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        21 22 23 24 25 26 27  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        28 29 30              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+        "###);
+    }
+
+    #[test]
+    fn test_print_fiscal_quarter_custom_start() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 8, 20).unwrap();
+        let args = args(["cal", "FYQ4", "--fiscal-year-start", "10"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+        "###);
+    }
+
+    #[test]
+    fn test_print_two_digit_year() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "24"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+                                                                        
+
+             April 2024             May 2024             June 2024      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7         1  2  3  4  5                  1  2
+         8  9 10 11 12 13 14   6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        15 16 17 18 19 20 21  13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        22 23 24 25 26 27 28  20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        29 30                 27 28 29 30 31        24 25 26 27 28 29 30
+                                                                        
+
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+
+            October 2024         November 2024         December 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+            1  2  3  4  5  6               1  2  3                     1
+         7  8  9 10 11 12 13   4  5  6  7  8  9 10   2  3  4  5  6  7  8
+        14 15 16 17 18 19 20  11 12 13 14 15 16 17   9 10 11 12 13 14 15
+        21 22 23 24 25 26 27  18 19 20 21 22 23 24  16 17 18 19 20 21 22
+        28 29 30 31           25 26 27 28 29 30     23 24 25 26 27 28 29
+                                                    30 31               
+        "###);
+    }
+
+    #[test]
+    fn test_print_two_digit_year_fiscal_quarter() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "FY24Q3"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_two_digit_year_fiscal_quarter_q1() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "FY25Q1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+        "###);
+    }
+
+    #[test]
+    fn test_print_future_fiscal_quarter() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "FY2090Q3"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2090         February 2090           March 2090     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+                           1         1  2  3  4  5         1  2  3  4  5
+         2  3  4  5  6  7  8   6  7  8  9 10 11 12   6  7  8  9 10 11 12
+         9 10 11 12 13 14 15  13 14 15 16 17 18 19  13 14 15 16 17 18 19
+        16 17 18 19 20 21 22  20 21 22 23 24 25 26  20 21 22 23 24 25 26
+        23 24 25 26 27 28 29  27 28                 27 28 29 30 31      
+        30 31                                                           
+        "###);
+    }
+
+    #[test]
+    fn test_print_quarter_show_year_only_once() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "2024Q1", "--show-year-only-once"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+                                      2024                              
+              January               February               March        
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_show_year_only_once_spanning_years() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        let args = args(["cal", "-B", "1", "-A", "1", "--show-year-only-once"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           December 2023          January 2024         February 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+                     1  2  3   1  2  3  4  5  6  7            1  2  3  4
+         4  5  6  7  8  9 10   8  9 10 11 12 13 14   5  6  7  8  9 10 11
+        11 12 13 14 15 16 17  15 16 17 18 19 20 21  12 13 14 15 16 17 18
+        18 19 20 21 22 23 24  22 23 24 25 26 27 28  19 20 21 22 23 24 25
+        25 26 27 28 29 30 31  29 30 31              26 27 28 29         
+        "###);
+    }
+
+    #[test]
+    fn test_nth_weekday_of_month() {
+        assert_eq!(
+            nth_weekday_of_month(2024, 3, Weekday::Tue, 2),
+            NaiveDate::from_ymd_opt(2024, 3, 12)
+        );
+        assert_eq!(
+            nth_weekday_of_month(2024, 3, Weekday::Fri, 5),
+            NaiveDate::from_ymd_opt(2024, 3, 29)
+        );
+        assert_eq!(nth_weekday_of_month(2024, 2, Weekday::Fri, 5), None);
+    }
+
+    #[test]
+    fn test_parse_nth_weekday() {
+        assert_eq!(
+            parse_nth_weekday("2:tue"),
+            Ok(NthWeekday {
+                n: 2,
+                weekday: Weekday::Tue
+            })
+        );
+        assert_eq!(
+            parse_nth_weekday("1:Monday"),
+            Ok(NthWeekday {
+                n: 1,
+                weekday: Weekday::Mon
+            })
+        );
+        assert!(parse_nth_weekday("tue").is_err());
+        assert!(parse_nth_weekday("0:tue").is_err());
+        assert!(parse_nth_weekday("6:tue").is_err());
+        assert!(parse_nth_weekday("2:tuesdayish").is_err());
+    }
+
+    #[test]
+    fn test_print_highlight_nth_weekday() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        let args = args([
+            "cal",
+            "2024-02",
+            "-A",
+            "1",
+            "--highlight-nth-weekday",
+            "2:tue",
+            "--highlight-nth-weekday",
+            "5:fri",
+        ]);
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+           February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+                  1  2  3  4               1  2  3
+         5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        12 \e[4m13\e[24m 14 15 16 17 18  11 \e[4m12\e[24m 13 14 15 16 17
+        19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        26 27 28 29           25 26 27 28 \e[4m29\e[24m 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_highlight_day_of_month() {
+        let _color = force_color("0");
+
+        // February doesn't have a 31st, so only the 1st is marked there
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "1",
+            "--highlight-day",
+            "1",
+            "--highlight-day",
+            "31",
+        ]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(show_edges(&print(args, current_date).unwrap()), @r###"
+        |    February 2024             March 2024      |
+        | Su Mo Tu We Th Fr Sa    Su Mo Tu We Th Fr Sa |
+        |            [ 1] 2  3                  [ 1] 2 |
+        |  4  5  6  7  8  9 10     3  4  5  6  7  8  9 |
+        | 11 12 13 14 15 16 17    10 11 12 13 14 15 16 |
+        | 18 19 20 21 22 23 24    17 18 19 20 21 22 23 |
+        | 25 26 27 28 29          24 25 26 27 28 29 30 |
+        |                        [31]                  |
+        "###);
+    }
+
+    #[test]
+    fn test_print_highlight_day_of_month_color() {
+        let _color = force_color("1");
+
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "--highlight-day-of-month",
+            "15",
+        ]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+           February 2024    
+        Su Mo Tu We Th Fr Sa
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 \e[4m15\e[24m 16 17
+        18 19 \e[7m20\e[27m 21 22 23 24
+        25 26 27 28 29      
+        "###);
+    }
+
+    #[test]
+    fn test_print_year_quarter_dividers() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "2024", "--quarter-dividers"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+                                                                        
+        ────────────────────────────────────────────────────────────────
+             April 2024             May 2024             June 2024      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7         1  2  3  4  5                  1  2
+         8  9 10 11 12 13 14   6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        15 16 17 18 19 20 21  13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        22 23 24 25 26 27 28  20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        29 30                 27 28 29 30 31        24 25 26 27 28 29 30
+                                                                        
+        ────────────────────────────────────────────────────────────────
+             July 2024            August 2024          September 2024   
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4                     1
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  16 17 18 19 20 21 22
+        29 30 31              26 27 28 29 30 31     23 24 25 26 27 28 29
+                                                    30                  
+        ────────────────────────────────────────────────────────────────
+            October 2024         November 2024         December 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+            1  2  3  4  5  6               1  2  3                     1
+         7  8  9 10 11 12 13   4  5  6  7  8  9 10   2  3  4  5  6  7  8
+        14 15 16 17 18 19 20  11 12 13 14 15 16 17   9 10 11 12 13 14 15
+        21 22 23 24 25 26 27  18 19 20 21 22 23 24  16 17 18 19 20 21 22
+        28 29 30 31           25 26 27 28 29 30     23 24 25 26 27 28 29
+                                                    30 31               
+        "###);
+    }
+
+    #[test]
+    fn test_print_group_by_quarter() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "3",
+            "--group-by-quarter",
+        ]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+                         Q1 2024                  
+           February 2024           March 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3                  1  2
+         4  5  6  7  8  9 10   3  4  5  6  7  8  9
+        11 12 13 14 15 16 17  10 11 12 13 14 15 16
+        18 19 20 21 22 23 24  17 18 19 20 21 22 23
+        25 26 27 28 29        24 25 26 27 28 29 30
+                              31                  
+
+                         Q2 2024                  
+             April 2024             May 2024      
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+            1  2  3  4  5  6            1  2  3  4
+         7  8  9 10 11 12 13   5  6  7  8  9 10 11
+        14 15 16 17 18 19 20  12 13 14 15 16 17 18
+        21 22 23 24 25 26 27  19 20 21 22 23 24 25
+        28 29 30              26 27 28 29 30 31   
+                                                  
+        "###);
+    }
+
+    #[test]
+    fn test_print_group_by_quarter_fiscal() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-06",
+            "-A",
+            "1",
+            "--group-by-quarter",
+            "--fiscal",
+            "--theme",
+            "solarized",
+        ]);
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+             \e[1;33mQ4 FY2024\e[22;39m      
+             \e[1;33mJune 2024\e[22;39m      
+        \e[36mSu Mo Tu We Th Fr Sa\e[39m
+                          \e[34m 1\e[39m
+        \e[34m 2\e[39m  3  4  5  6  7 \e[34m 8\e[39m
+        \e[34m 9\e[39m 10 11 12 13 14 \e[34m15\e[39m
+        \e[34m16\e[39m 17 18 19 20 21 \e[34m22\e[39m
+        \e[34m23\e[39m 24 25 26 27 28 \e[34m29\e[39m
+        \e[34m30\e[39m                  
+
+             \e[1;33mQ1 FY2025\e[22;39m      
+             \e[1;33mJuly 2024\e[22;39m      
+        \e[36mSu Mo Tu We Th Fr Sa\e[39m
+            1  2  3  4  5 \e[34m 6\e[39m
+        \e[34m 7\e[39m  8  9 10 11 12 \e[34m13\e[39m
+        \e[34m14\e[39m 15 16 17 18 19 \e[34m20\e[39m
+        \e[34m21\e[39m 22 23 24 25 26 \e[34m27\e[39m
+        \e[34m28\e[39m 29 30 31         
+                            
+        "###);
+    }
+
+    #[test]
+    fn test_print_shared_weekday_header() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let stacked = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024Q1",
+            "--columns",
+            "1",
+            "--shared-weekday-header",
+        ]);
+
+        insta::assert_snapshot!(print(stacked, current_date).unwrap(), @r###"
+        Su Mo Tu We Th Fr Sa
+            January 2024    
+            1  2  3  4  5  6
+         7  8  9 10 11 12 13
+        14 15 16 17 18 19 20
+        21 22 23 24 25 26 27
+        28 29 30 31         
+                            
+
+           February 2024    
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29      
+                            
+
+             March 2024     
+                        1  2
+         3  4  5  6  7  8  9
+        10 11 12 13 14 15 16
+        17 18 19 20 21 22 23
+        24 25 26 27 28 29 30
+        31                  
+        "###);
+
+        // months side by side each keep their own header
+        let side_by_side = args(["cal", "-f", "sunday", "2024Q1", "--shared-weekday-header"]);
+        assert_eq!(
+            print(side_by_side, current_date)
+                .unwrap()
+                .matches("Su Mo Tu We Th Fr Sa")
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_quarter_from_month() {
+        let quarters = |style, fiscal_year_start| {
+            (1..=12)
+                .map(|month| Quarter::from_month(month, style, fiscal_year_start).number())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            quarters(YearStyle::Calendar, 7),
+            vec![1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4]
+        );
+        assert_eq!(
+            quarters(YearStyle::Fiscal, 7),
+            vec![3, 3, 3, 4, 4, 4, 1, 1, 1, 2, 2, 2]
+        );
+        assert_eq!(
+            quarters(YearStyle::Fiscal, 10),
+            vec![2, 2, 2, 3, 3, 3, 4, 4, 4, 1, 1, 1]
+        );
+        assert_eq!(
+            quarters(YearStyle::Fiscal, 2),
+            vec![4, 1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4]
+        );
+        assert_eq!(
+            quarters(YearStyle::Fiscal, 1),
+            quarters(YearStyle::Calendar, 1)
+        );
+        assert_eq!(Quarter::from_month(8, YearStyle::Fiscal, 7), Quarter::Q1);
+    }
+
+    #[test]
+    fn test_quarter_label() {
+        let date = |year, month| NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let calendar = RenderOptions::default();
+        let fiscal = RenderOptions {
+            fiscal_year_start: Some(7),
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(calendar.quarter_label(date(2024, 1)), "Q1 2024");
+        assert_eq!(calendar.quarter_label(date(2024, 12)), "Q4 2024");
+        assert_eq!(fiscal.quarter_label(date(2024, 6)), "Q4 FY2024");
+        assert_eq!(fiscal.quarter_label(date(2024, 7)), "Q1 FY2025");
+        assert_eq!(fiscal.quarter_label(date(2024, 11)), "Q2 FY2025");
+    }
+
+    #[test]
+    fn test_print_quarter_dividers_two_columns() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "2024-01",
+            "-A",
+            "5",
+            "--columns",
+            "2",
+            "--quarter-dividers",
+        ]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25
+        29 30 31              26 27 28 29         
+
+             March 2024            April 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+                     1  2  3   1  2  3  4  5  6  7
+         4  5  6  7  8  9 10   8  9 10 11 12 13 14
+        11 12 13 14 15 16 17  15 16 17 18 19 20 21
+        18 19 20 21 22 23 24  22 23 24 25 26 27 28
+        25 26 27 28 29 30 31  29 30               
+
+              May 2024             June 2024      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+               1  2  3  4  5                  1  2
+         6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        27 28 29 30 31        24 25 26 27 28 29 30
+        "###);
+    }
+
+    #[test]
+    fn test_print_ascii_today_marker() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--ascii-today-marker"]);
+
+        insta::assert_snapshot!(show_edges(&print(args, current_date).unwrap()), @r###"
+        |      March 2024      |
+        | Mo Tu We Th Fr Sa Su |
+        |              1  2  3 |
+        |  4  5  6  7  8  9 10 |
+        | 11 12 13 14 15 16 17 |
+        | 18 19[20]21 22 23 24 |
+        | 25 26 27 28 29 30 31 |
+        "###);
+    }
+
+    #[test]
+    fn test_print_no_empty_trailing_row() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        // June needs six rows, the first quarter only needs five
+        let args = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-01",
+            "-A",
+            "5",
+            "--no-empty-trailing-row",
+        ]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024         February 2024           March 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7            1  2  3  4               1  2  3
+         8  9 10 11 12 13 14   5  6  7  8  9 10 11   4  5  6  7  8  9 10
+        15 16 17 18 19 20 21  12 13 14 15 16 17 18  11 12 13 14 15 16 17
+        22 23 24 25 26 27 28  19 20 21 22 23 24 25  18 19 20 21 22 23 24
+        29 30 31              26 27 28 29           25 26 27 28 29 30 31
+
+             April 2024             May 2024             June 2024      
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7         1  2  3  4  5                  1  2
+         8  9 10 11 12 13 14   6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        15 16 17 18 19 20 21  13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        22 23 24 25 26 27 28  20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        29 30                 27 28 29 30 31        24 25 26 27 28 29 30
+        "###);
+    }
+
+    #[test]
+    fn test_print_anchor_today_column() {
+        // a Wednesday
+        let current_date = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+        let args = || args(["cal", "-f", "sunday", "2024-04", "--anchor-today-column"]);
+
+        let plain = {
+            let _color = force_color("0");
+            print(args(), current_date).unwrap()
+        };
+        assert!(!plain.contains('\x1B'));
+
+        let _color = force_color("1");
+        insta::assert_snapshot!(escape_ansi(&print(args(), current_date).unwrap()), @r###"
+             April 2024     
+        Su Mo Tu \e[48;5;236mWe\e[49m Th Fr Sa
+            1  2 \e[48;5;236m\e[7m 3\e[27m\e[49m  4  5  6
+         7  8  9 \e[48;5;236m10\e[49m 11 12 13
+        14 15 16 \e[48;5;236m17\e[49m 18 19 20
+        21 22 23 \e[48;5;236m24\e[49m 25 26 27
+        28 29 30 \e[48;5;236m  \e[49m         
+        "###);
+    }
+
+    #[test]
+    fn test_print_highlight_current_week() {
+        // the week of March 31st continues into April
+        let current_date = NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+        let args = || {
+            args([
+                "cal",
+                "-f",
+                "sunday",
+                "2024-03",
+                "-A",
+                "1",
+                "--highlight-current-week",
+            ])
+        };
+
+        let plain = {
+            let _color = force_color("0");
+            print(args(), current_date).unwrap()
+        };
+        insta::assert_snapshot!(show_edges(&plain), @r###"
+        |      March 2024              April 2024      |
+        | Su Mo Tu We Th Fr Sa    Su Mo Tu We Th Fr Sa |
+        |                 1  2   >    1  2  3  4  5  6 |
+        |  3  4  5  6  7  8  9     7  8  9 10 11 12 13 |
+        | 10 11 12 13 14 15 16    14 15 16 17 18 19 20 |
+        | 17 18 19 20 21 22 23    21 22 23 24 25 26 27 |
+        | 24 25 26 27 28 29 30    28 29 30             |
+        |>31                                           |
+        "###);
+
+        let _color = force_color("1");
+        insta::assert_snapshot!(escape_ansi(&print(args(), current_date).unwrap()), @r###"
+             March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                        1  2  \e[100m    1 \e[7m 2\e[27m  3  4  5  6\e[49m
+         3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        24 25 26 27 28 29 30  28 29 30            
+        \e[100m31                  \e[49m                      
+        "###);
+    }
+
+    #[test]
+    fn test_color() {
+        assert_eq!(parse_color("208"), Ok(Color::Indexed(208)));
+        assert_eq!(parse_color("#ff8800"), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!(parse_color("#FF8800"), Ok(Color::Rgb(255, 136, 0)));
+        assert!(parse_color("256").is_err());
+        assert!(parse_color("#ff88").is_err());
+        assert!(parse_color("#gg8800").is_err());
+        assert!(parse_color("orange").is_err());
+
+        assert_eq!(
+            Color::Indexed(208).foreground(ColorDepth::Truecolor),
+            "38;5;208"
+        );
+        assert_eq!(
+            Color::Indexed(208).foreground(ColorDepth::Palette),
+            "38;5;208"
+        );
+        assert_eq!(
+            Color::Rgb(255, 136, 0).foreground(ColorDepth::Truecolor),
+            "38;2;255;136;0"
+        );
+        assert_eq!(
+            Color::Rgb(255, 136, 0).foreground(ColorDepth::Palette),
+            "38;5;208"
+        );
+        assert_eq!(
+            Color::Rgb(0, 0, 0).foreground(ColorDepth::Palette),
+            "38;5;16"
+        );
+        assert_eq!(
+            Color::Rgb(255, 255, 255).foreground(ColorDepth::Palette),
+            "38;5;231"
+        );
+
+        // on 16-color terminals everything is approximated by the nearest basic color
+        assert_eq!(Color::Indexed(1).foreground(ColorDepth::Basic), "31");
+        assert_eq!(Color::Indexed(12).background(ColorDepth::Basic), "104");
+        assert_eq!(Color::Indexed(196).foreground(ColorDepth::Basic), "91");
+        assert_eq!(Color::Indexed(236).background(ColorDepth::Basic), "40");
+        assert_eq!(Color::Indexed(250).foreground(ColorDepth::Basic), "37");
+        assert_eq!(Color::Rgb(255, 136, 0).foreground(ColorDepth::Basic), "33");
+    }
+
+    #[test]
+    fn test_print_color_depth() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let print_depth = |depth: &str| {
+            let args = args([
+                "cal",
+                "-f",
+                "sunday",
+                "2024-03",
+                "--theme",
+                "solarized",
+                "--highlight-current-week",
+                "--highlight-day",
+                "8",
+                "--highlight-color",
+                "#ff8800",
+                "--color-depth",
+                depth,
+            ]);
+            let output = print(args, current_date).unwrap();
+
+            // just the current week, which has the theme's background and the highlight color
+            escape_ansi(output.lines().nth(3).unwrap())
+        };
+
+        insta::assert_snapshot!(print_depth("16"), @r###"
+        \e[40m\e[34m 3\e[39m  4 \e[7;33m 5\e[27;39m  6  7 \e[33m 8\e[39m \e[34m 9\e[39m\e[49m
+        "###);
+        insta::assert_snapshot!(print_depth("256"), @r###"
+        \e[48;5;235m\e[34m 3\e[39m  4 \e[7;33m 5\e[27;39m  6  7 \e[38;5;208m 8\e[39m \e[34m 9\e[39m\e[49m
+        "###);
+        insta::assert_snapshot!(print_depth("truecolor"), @r###"
+        \e[48;5;235m\e[34m 3\e[39m  4 \e[7;33m 5\e[27;39m  6  7 \e[38;2;255;136;0m 8\e[39m \e[34m 9\e[39m\e[49m
+        "###);
+    }
+
+    #[test]
+    fn test_style_with_depth() {
+        let style = Style::sgr("1;38;5;196;48;2;0;0;238", "22;39;49");
+
+        assert_eq!(style.with_depth(ColorDepth::Truecolor), style);
+        assert_eq!(
+            style.with_depth(ColorDepth::Palette),
+            Style::sgr("1;38;5;196;48;5;21", "22;39;49")
+        );
+        assert_eq!(
+            style.with_depth(ColorDepth::Basic),
+            Style::sgr("1;91;44", "22;39;49")
+        );
+        assert_eq!(Style::none().with_depth(ColorDepth::Basic), Style::none());
+    }
+
+    #[test]
+    fn test_print_highlight_color() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "--highlight-day",
+            "1",
+            "--highlight-color",
+            "208",
+        ]);
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+             March 2024     
+        Su Mo Tu We Th Fr Sa
+                       \e[38;5;208m 1\e[39m  2
+         3  4  5  6  7  8  9
+        10 11 12 13 14 15 16
+        17 18 19 20 21 22 23
+        24 25 26 27 28 29 30
+        31                  
+        "###);
+    }
+
+    #[test]
+    fn test_print_header_rule() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let line = args(["cal", "-f", "sunday", "2024-03", "-A", "1", "--header-rule"]);
+
+        insta::assert_snapshot!(print(line, current_date).unwrap(), @r###"
+             March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+        ────────────────────  ────────────────────
+                        1  2      1  2  3  4  5  6
+         3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        24 25 26 27 28 29 30  28 29 30            
+        31                                        
+        "###);
+
+        let dash = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "--header-rule",
+            "dash",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(dash, current_date).unwrap()), @r###"
+        |      March 2024      |
+        | Su Mo Tu We Th Fr Sa |
+        |----------------------|
+        |                 1  2 |
+        |  3  4  5  6  7  8  9 |
+        | 10 11 12 13 14 15 16 |
+        | 17 18 19 20 21 22 23 |
+        | 24 25 26 27 28 29 30 |
+        | 31                   |
+        "###);
+    }
+
+    #[test]
+    fn test_print_cell_content() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let weekday_index = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "--cell-content",
+            "weekday-index",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(weekday_index, current_date).unwrap()), @r###"
+        |     March 2024     |
+        |Su Mo Tu We Th Fr Sa|
+        |                6  7|
+        | 1  2  3  4  5  6  7|
+        | 1  2  3  4  5  6  7|
+        | 1  2  3  4  5  6  7|
+        | 1  2  3  4  5  6  7|
+        | 1                  |
+        "###);
+
+        let day_of_year = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-02",
+            "-A",
+            "1",
+            "--cell-content",
+            "day-of-year",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(day_of_year, current_date).unwrap()), @r###"
+        |       February 2024                 March 2024         |
+        | Mo  Tu  We  Th  Fr  Sa  Su   Mo  Tu  We  Th  Fr  Sa  Su|
+        |             32  33  34  35                   61  62  63|
+        | 36  37  38  39  40  41  42   64  65  66  67  68  69  70|
+        | 43  44  45  46  47  48  49   71  72  73  74  75  76  77|
+        | 50  51  52  53  54  55  56   78  79  80  81  82  83  84|
+        | 57  58  59  60               85  86  87  88  89  90  91|
+        "###);
+
+        let marked = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-05",
+            "--cell-content",
+            "day-of-year",
+            "--day-pad",
+            "zero",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(marked, current_date).unwrap()), @r###"
+        |          May 2024           |
+        |  Mo  Tu  We  Th  Fr  Sa  Su |
+        |         122 123 124 125 126 |
+        | 127 128 129 130 131 132 133 |
+        | 134 135 136 137 138 139 140 |
+        |[141]142 143 144 145 146 147 |
+        | 148 149 150 151 152         |
+        "###);
+    }
+
+    #[test]
+    fn test_print_day_gap() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let thin = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "-A",
+            "1",
+            "--day-gap",
+            "thin",
+        ]);
+
+        insta::assert_snapshot!(print(thin, current_date).unwrap(), @r###"
+             March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                        1  2      1  2  3  4  5  6
+         3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        24 25 26 27 28 29 30  28 29 30            
+        31                                        
+        "###);
+
+        let none = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-05",
+            "--day-gap",
+            "none",
+            "--day-pad",
+            "zero",
+            "--header-rule",
+            "dash",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(none, current_date).unwrap()), @r###"
+        |   May 2024   |
+        |SuMoTuWeThFrSa|
+        |--------------|
+        |      01020304|
+        |05060708091011|
+        |12131415161718|
+        |19202122232425|
+        |262728293031  |
+        "###);
+
+        // the markers need a gutter, so the regular space is kept
+        let marked = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-05",
+            "--day-gap",
+            "none",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(marked, current_date).unwrap()), @r###"
+        |       May 2024       |
+        | Su Mo Tu We Th Fr Sa |
+        |           1  2  3  4 |
+        |  5  6  7  8  9 10 11 |
+        | 12 13 14 15 16 17 18 |
+        | 19[20]21 22 23 24 25 |
+        | 26 27 28 29 30 31    |
+        "###);
+
+        let thin_marked = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-05",
+            "--day-gap",
+            "thin",
+            "--ascii-today-marker",
+        ]);
+
+        assert_eq!(
+            print(thin_marked, current_date),
+            print(
+                args(["cal", "-f", "sunday", "2024-05", "--ascii-today-marker"]),
+                current_date
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_force_weeks() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let truncated = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "-A",
+            "1",
+            "--force-weeks",
+            "4",
+        ]);
+
+        insta::assert_snapshot!(print(truncated, current_date).unwrap(), @r###"
+             March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                        1  2      1  2  3  4  5  6
+         3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        "###);
+
+        let extended = args(["cal", "-f", "sunday", "2024-02", "--force-weeks", "6"]);
+
+        insta::assert_snapshot!(show_edges(&print(extended, current_date).unwrap()), @r###"
+        |   February 2024    |
+        |Su Mo Tu We Th Fr Sa|
+        |             1  2  3|
+        | 4  5  6  7  8  9 10|
+        |11 12 13 14 15 16 17|
+        |18 19 20 21 22 23 24|
+        |25 26 27 28 29      |
+        |                    |
+        "###);
+        assert!(Arguments::try_parse_from(["cal", "--force-weeks", "7"]).is_err());
+    }
+
+    #[test]
+    fn test_print_append_weeknum_suffix() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let monday_first = args(["cal", "-f", "monday", "2024-03", "--append-weeknum-suffix"]);
+
+        insta::assert_snapshot!(show_edges(&print(monday_first, current_date).unwrap()), @r###"
+        |      March 2024      |
+        | Mo Tu We Th Fr Sa Su |
+        |              1  2  3 |
+        |W10  5  6  7  8  9 10 |
+        |W11 12 13 14 15 16 17 |
+        |W12 19 20 21 22 23 24 |
+        |W13 26 27 28 29 30 31 |
+        "###);
+
+        let sunday_first = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-03",
+            "--append-weeknum-suffix",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(sunday_first, current_date).unwrap()), @r###"
+        |      March 2024      |
+        | Su Mo Tu We Th Fr Sa |
+        |                 1  2 |
+        |  3W10  5  6  7  8  9 |
+        | 10W11 12 13 14 15 16 |
+        | 17W12 19[20]21 22 23 |
+        | 24W13 26 27 28 29 30 |
+        | 31                   |
+        "###);
+    }
+
+    #[test]
+    fn test_print_ascii_today_marker_edge_of_row() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 17).unwrap();
+        let args = args(["cal", "--ascii-today-marker", "-B", "1", "-A", "1"]);
+
+        insta::assert_snapshot!(show_edges(&print(args, current_date).unwrap()), @r###"
+        |    February 2024             March 2024              April 2024      |
+        | Mo Tu We Th Fr Sa Su    Mo Tu We Th Fr Sa Su    Mo Tu We Th Fr Sa Su |
+        |           1  2  3  4                 1  2  3     1  2  3  4  5  6  7 |
+        |  5  6  7  8  9 10 11     4  5  6  7  8  9 10     8  9 10 11 12 13 14 |
+        | 12 13 14 15 16 17 18    11 12 13 14 15 16[17]   15 16 17 18 19 20 21 |
+        | 19 20 21 22 23 24 25    18 19 20 21 22 23 24    22 23 24 25 26 27 28 |
+        | 26 27 28 29             25 26 27 28 29 30 31    29 30                |
+        "###);
+    }
+
+    #[test]
+    fn test_determine_date_ranges_context() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--context", "1"])).unwrap(),
+            vec![(date(2024, 2, 1), date(2024, 4, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--context", "1", "-A", "2"]))
+                .unwrap(),
+            vec![(date(2024, 2, 1), date(2024, 5, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--context", "1", "2024-06"]))
+                .unwrap(),
+            vec![(date(2024, 6, 1), date(2024, 6, 30))]
+        );
+    }
+
+    #[test]
+    fn test_config_context() {
+        let config = Config::parse("context = 1").unwrap();
+        assert_eq!(config.context, Some(1));
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal"]).with_config(&config)).unwrap(),
+            vec![(date(2024, 2, 1), date(2024, 4, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--context", "2"]).with_config(&config)
+            )
+            .unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 5, 31))]
+        );
+
+        assert_eq!(
+            Config::parse("context = 13"),
+            Err("context must be 1 - 12, got: 13".to_string())
+        );
+        assert!(Config::parse("unknown = true").is_err());
+    }
+
+    #[test]
+    fn test_config_periods() {
+        let config = Config::parse(
+            r#"
+            [periods]
+            summer = "06-01..08-31"
+            winter = "12-01..02-29"
+            "#,
+        )
+        .unwrap();
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let ranges = |argv: &[&str]| {
+            let args =
+                Arguments::try_parse_argv(argv.iter().map(OsString::from).collect(), &config)
+                    .unwrap();
+            let date_input = args
+                .date_input
+                .iter()
+                .map(|date_input| {
+                    resolve_date_input(current_date, 1, None, date_input.clone()).unwrap()
+                })
+                .collect();
+
+            determine_date_ranges(current_date, Arguments { date_input, ..args }).unwrap()
+        };
+
+        assert_eq!(
+            ranges(&["cal", "summer"]),
+            vec![(date(2024, 6, 1), date(2024, 8, 31))]
+        );
+        assert_eq!(
+            ranges(&["cal", "2023summer"]),
+            vec![(date(2023, 6, 1), date(2023, 8, 31))]
+        );
+        assert_eq!(
+            ranges(&["cal", "winter"]),
+            vec![(date(2024, 12, 1), date(2025, 2, 28))]
+        );
+        assert_eq!(
+            ranges(&["cal", "2023winter"]),
+            vec![(date(2023, 12, 1), date(2024, 2, 29))]
+        );
+
+        // unknown names, other years, and the values of other options aren't periods
+        let parse = |argv: &[&str]| {
+            Arguments::try_parse_argv(argv.iter().map(OsString::from).collect(), &config)
+        };
+        assert!(parse(&["cal", "spring"]).is_err());
+        assert!(parse(&["cal", "24summer"]).is_err());
+        let args = parse(&["cal", "--overlay-notes", "summer"]).unwrap();
+        assert_eq!(args.overlay_notes, Some(PathBuf::from("summer")));
+        assert!(args.date_input.is_empty());
+        assert!(Arguments::try_parse_argv(
+            ["cal", "summer"].iter().map(OsString::from).collect(),
+            &Config::default()
+        )
+        .is_err());
+
+        assert_eq!(
+            Config::parse("[periods]\nsummer = \"2024Q3\""),
+            Err("period summer must be a range like 06-01..08-31, got: 2024Q3".to_string())
+        );
+        assert!(Config::parse("[periods]\nQ3 = \"07-01..09-30\"").is_err());
+    }
+
+    #[test]
+    fn test_determine_date_ranges_zero_months_before_and_after() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        // `--months-before 0` and `--months-after 0` are rejected on the command line
+        let args = Arguments {
+            date_input: vec![DateInput::YearMonth(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: 2024,
+                },
+                1,
+            )],
+            months_before: Some(0),
+            months_after: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            determine_date_ranges(current_date, args).unwrap(),
+            vec![(
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_multiple_years_before_and_after() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "-B", "25"])).unwrap(),
+            vec![(date(2022, 2, 1), date(2024, 3, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "-A", "30"])).unwrap(),
+            vec![(date(2024, 3, 1), date(2026, 9, 30))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "2024-03", "-B", "25", "-A", "30"])
+            )
+            .unwrap(),
+            vec![(date(2022, 2, 1), date(2026, 9, 30))]
+        );
+        // exactly a year either way lands on the same month
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "-B", "12", "-A", "24"])).unwrap(),
+            vec![(date(2023, 3, 1), date(2026, 3, 31))]
+        );
+    }
+
+    #[test]
+    fn test_add_months() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        for (start, delta, expected) in [
+            (date(2024, 3, 15), 0, date(2024, 3, 15)),
+            (date(2024, 3, 15), 1, date(2024, 4, 15)),
+            (date(2024, 3, 15), -1, date(2024, 2, 15)),
+            // year rollover in either direction
+            (date(2024, 12, 1), 1, date(2025, 1, 1)),
+            (date(2024, 1, 1), -1, date(2023, 12, 1)),
+            (date(2024, 3, 1), 30, date(2026, 9, 1)),
+            (date(2024, 3, 1), -25, date(2022, 2, 1)),
+            (date(2024, 3, 1), -27, date(2021, 12, 1)),
+            (date(2024, 3, 1), 120, date(2034, 3, 1)),
+            // days past the end of the month are clamped, leap years get the 29th
+            (date(2024, 1, 31), 1, date(2024, 2, 29)),
+            (date(2023, 1, 31), 1, date(2023, 2, 28)),
+            (date(2024, 3, 31), -1, date(2024, 2, 29)),
+            (date(2024, 2, 29), 12, date(2025, 2, 28)),
+            (date(2024, 2, 29), 48, date(2028, 2, 29)),
+            (date(2024, 5, 31), 1, date(2024, 6, 30)),
+            (date(2024, 8, 31), -2, date(2024, 6, 30)),
+            // clamping doesn't carry over to later months
+            (date(2024, 1, 31), 2, date(2024, 3, 31)),
+            // the first and last representable months
+            (NaiveDate::MAX, -11, date(NaiveDate::MAX.year(), 1, 31)),
+            (NaiveDate::MIN, 11, date(NaiveDate::MIN.year(), 12, 1)),
+        ] {
+            assert_eq!(
+                add_months(start, delta),
+                Ok(expected),
+                "{} {:+}",
+                start,
+                delta
+            );
+        }
+
+        assert_eq!(
+            add_months(NaiveDate::MAX, 1),
+            Err(CalError::OutOfRange(format!(
+                "{}-01",
+                NaiveDate::MAX.year() + 1
+            )))
+        );
+        assert_eq!(
+            add_months(NaiveDate::MIN, -1),
+            Err(CalError::OutOfRange(format!(
+                "{}-12",
+                NaiveDate::MIN.year() - 1
+            )))
+        );
+        assert_eq!(
+            add_months(date(2024, 3, 1), -(u32::MAX as i64)),
+            Err(CalError::OutOfRange("-357911918-12".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_out_of_range() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let max_year = NaiveDate::MAX.year();
+        let min_year = NaiveDate::MIN.year();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--year", "262142"])).unwrap(),
+            vec![(
+                NaiveDate::from_ymd_opt(max_year, 1, 1).unwrap(),
+                NaiveDate::MAX
+            )]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--year", "262142", "-A", "12"])),
+            Err(CalError::OutOfRange("262143-12".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--year", "262143"])),
+            Err(CalError::OutOfRange("262143".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--year=-262143", "-B", "1"])),
+            Err(CalError::OutOfRange(format!("{}-12", min_year - 1)))
+        );
+        // months and quarters past the last representable year
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "262143-12"])),
+            Err(CalError::OutOfRange("262143-12".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "262143Q4"])),
+            Err(CalError::OutOfRange("262143-01".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "FY262143Q1-Q4"])),
+            Err(CalError::OutOfRange("262143-06".to_string()))
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--start-date", "262143-12", "--num-months", "3"])
+            ),
+            Err(CalError::OutOfRange("262143-12".to_string()))
+        );
+        assert_eq!(
+            print(args(["cal", "--year", "262143"]), current_date)
+                .unwrap_err()
+                .to_string(),
+            "262143 is outside of the supported range of years (-262143 to 262142)"
+        );
+    }
+
+    #[test]
+    fn test_determine_date_ranges_to_date() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--ytd"])).unwrap(),
+            vec![(date(2024, 1, 1), current_date)]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--qtd"])).unwrap(),
+            vec![(date(2024, 4, 1), current_date)]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--ytd", "--fiscal"])).unwrap(),
+            vec![(date(2023, 7, 1), current_date)]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--qtd", "--fiscal"])).unwrap(),
+            vec![(date(2024, 4, 1), current_date)]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--qtd", "--fiscal", "--fiscal-year-start", "2"])
+            )
+            .unwrap(),
+            vec![(date(2024, 5, 1), current_date)]
+        );
+    }
+
+    #[test]
+    fn test_print_qtd() {
+        let _color = force_color("0");
+
+        let args = args(["cal", "-f", "sunday", "--qtd"]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             April 2024             May 2024      
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+            1  2  3  4  5  6            1  2  3  4
+         7  8  9 10 11 12 13   5  6  7  8  9 10 11
+        14 15 16 17 18 19 20  12 13 14 15 16 17 18
+        21 22 23 24 25 26 27  19 20               
+        28 29 30                                  
+        "###);
+    }
+
+    #[test]
+    fn test_determine_date_ranges_three_and_one() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 1, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "-3"])).unwrap(),
+            vec![(date(2023, 12, 1), date(2024, 2, 29))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--three", "2024-06"])).unwrap(),
+            vec![(date(2024, 5, 1), date(2024, 7, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "-1", "--context", "2"])).unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 1, 31))]
+        );
+        assert!(Arguments::try_parse_from(["cal", "-3", "-A", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_determine_date_ranges_around() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--around", "2024-06-15", "--radius", "2"])
+            )
+            .unwrap(),
+            vec![(date(2024, 4, 1), date(2024, 8, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--around", "2025-01-10", "--radius", "2"])
+            )
+            .unwrap(),
+            vec![(date(2024, 11, 1), date(2025, 3, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--around", "2024-12-25"])).unwrap(),
+            vec![(date(2024, 12, 1), date(2024, 12, 31))]
+        );
+        // `--context` only applies to the default view
+        assert_eq!(
+            determine_date_ranges(
+                current_date,
+                args(["cal", "--around", "2024-12-25", "--context", "3"])
+            )
+            .unwrap(),
+            vec![(date(2024, 12, 1), date(2024, 12, 31))]
+        );
+
+        assert!(Arguments::try_parse_from(["cal", "--radius", "2"]).is_err());
+        assert!(Arguments::try_parse_from(["cal", "--around", "2024-06-15", "-A", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_print_around_highlights_month() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "--around",
+            "2024-06-15",
+            "--radius",
+            "1",
+        ]);
+        let output = print(args, current_date).unwrap();
+
+        insta::assert_snapshot!(show_edges(&escape_ansi(output.lines().next().unwrap())), @r###"
+        |      May 2024             \e[4mJune 2024\e[24m             July 2024      |
+        "###);
+    }
+
+    #[test]
+    fn test_determine_date_ranges_start_date_and_num_months() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let ranges = |start_date: &str, num_months: &str| {
+            determine_date_ranges(
+                current_date,
+                args([
+                    "cal",
+                    "--start-date",
+                    start_date,
+                    "--num-months",
+                    num_months,
+                ]),
+            )
+        };
+
+        assert_eq!(
+            ranges("2024-03", "5").unwrap(),
+            vec![(date(2024, 3, 1), date(2024, 7, 31))]
+        );
+        assert_eq!(
+            ranges("2024-03", "1").unwrap(),
+            vec![(date(2024, 3, 1), date(2024, 3, 31))]
+        );
+        assert_eq!(
+            ranges("2024-11", "4").unwrap(),
+            vec![(date(2024, 11, 1), date(2025, 2, 28))]
+        );
+        assert_eq!(
+            ranges("2023-12", "26").unwrap(),
+            vec![(date(2023, 12, 1), date(2026, 1, 31))]
+        );
+        assert_eq!(
+            ranges("262142-11", "3"),
+            Err(CalError::OutOfRange("262143-01".to_string()))
+        );
+
+        assert!(Arguments::try_parse_from(["cal", "--start-date", "2024-03"]).is_err());
+        assert!(Arguments::try_parse_from(["cal", "--num-months", "3"]).is_err());
+        assert!(
+            Arguments::try_parse_from(["cal", "--start-date", "2024-03", "--num-months", "0"])
+                .is_err()
+        );
+        assert!(Arguments::try_parse_from([
+            "cal",
+            "2024",
+            "--start-date",
+            "2024-03",
+            "--num-months",
+            "3"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_determine_date_ranges_whole_year() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--whole-year"])).unwrap(),
+            vec![(date(2024, 1, 1), date(2024, 12, 31))]
+        );
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "--whole-year", "2023-03"])).unwrap(),
+            vec![(date(2023, 1, 1), date(2023, 12, 31))]
+        );
+    }
+
+    #[test]
+    fn test_util_linux_args() {
+        let parse = |argv: &[&str]| {
+            Arguments::try_parse_argv(
+                argv.iter().map(OsString::from).collect(),
+                &Config::default(),
+            )
+        };
+
+        // native meanings without `--compat util-linux`
+        let native = parse(&["cal", "-m", "3", "-y", "2024"]).unwrap();
+        assert_eq!((native.year, native.month), (Some(2024), Some(3)));
+        assert!(parse(&["cal", "-s"]).is_err());
+
+        let util_linux = parse(&["cal", "--compat", "util-linux", "-3m", "--", "2024-06"]).unwrap();
+        assert!(util_linux.three);
+        assert_eq!(util_linux.first_day_of_week, Some(FirstDayOfWeek::Monday));
+        assert_eq!(util_linux.date_input.len(), 1);
+        assert!(!util_linux.whole_year);
+
+        // mistakes are reported with the util-linux meanings
+        let err = parse(&["cal", "--compat", "util-linux", "-3m", "-A", "2"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        let util_linux = parse(&["cal", "-sy", "--compat=util-linux"]).unwrap();
+        assert_eq!(util_linux.first_day_of_week, Some(FirstDayOfWeek::Sunday));
+        assert!(util_linux.whole_year);
+        assert_eq!(util_linux.year, None);
+
+        // `--year` and `--month` keep their long names
+        let util_linux = parse(&["cal", "--compat=util-linux", "--year", "2024"]).unwrap();
+        assert_eq!(util_linux.year, Some(2024));
+
+        let err = parse(&["cal", "--compat=util-linux", "-j"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("-j (Julian days) is not supported"));
+
+        // `--compat util-linux` only counts when it's an option, not after `--` or as another
+        // option's value
+        assert!(parse(&["cal", "-s", "--", "--compat", "util-linux"]).is_err());
+        assert!(parse(&["cal", "-y", "2024", "--", "--compat=util-linux"]).is_err());
+        let native = parse(&[
+            "cal",
+            "-m",
+            "3",
+            "-y",
+            "2024",
+            "--overlay-notes=--compat=util-linux",
+        ])
+        .unwrap();
+        assert_eq!(native.compat, Compat::Native);
+        assert_eq!((native.year, native.month), (Some(2024), Some(3)));
+    }
+
+    #[test]
+    fn test_determine_date_ranges_multiple_inputs() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            determine_date_ranges(current_date, args(["cal", "2024-01", "2024-06", "-A", "1"]))
+                .unwrap(),
+            vec![
+                (date(2024, 1, 1), date(2024, 2, 29)),
+                (date(2024, 6, 1), date(2024, 7, 31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_multiple_months() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args(["cal", "2024-12", "2024-01", "2024-06", "2024-01"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+            January 2024           June 2024           December 2024    
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+         1  2  3  4  5  6  7                  1  2                     1
+         8  9 10 11 12 13 14   3  4  5  6  7  8  9   2  3  4  5  6  7  8
+        15 16 17 18 19 20 21  10 11 12 13 14 15 16   9 10 11 12 13 14 15
+        22 23 24 25 26 27 28  17 18 19 20 21 22 23  16 17 18 19 20 21 22
+        29 30 31              24 25 26 27 28 29 30  23 24 25 26 27 28 29
+                                                    30 31               
+        "###);
+    }
+
+    #[test]
+    fn test_print_invert() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--invert"]);
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+        \e[7m     March 2024     \e[27m
+        \e[7mMo Tu We Th Fr Sa Su\e[27m
+        \e[7m             1  2  3\e[27m
+        \e[7m 4  5  6  7  8  9 10\e[27m
+        \e[7m11 12 13 14 15 16 17\e[27m
+        \e[7m18 19 \e[27m20\e[7m 21 22 23 24\e[27m
+        \e[7m25 26 27 28 29 30 31\e[27m
+        "###);
+    }
+
+    #[test]
+    fn test_print_past_style() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+        let args = args(["cal", "-f", "monday", "--past-style", "strike"]);
+
+        insta::assert_snapshot!(escape_ansi(&print(args, current_date).unwrap()), @r###"
+             March 2024     
+        Mo Tu We Th Fr Sa Su
+                    \e[9m 1\e[29m \e[9m 2\e[29m \e[9m 3\e[29m
+        \e[9m 4\e[29m \e[9m 5\e[29m \e[9m 6\e[29m \e[9m 7\e[29m \e[9m 8\e[29m \e[9m 9\e[29m \e[9m10\e[29m
+        \e[9m11\e[29m \e[9m12\e[29m \e[7m13\e[27m 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_past_style_without_color() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap();
+
+        assert_eq!(
+            print(args(["cal", "--past-style", "dim"]), current_date).unwrap(),
+            print(args(["cal"]), current_date).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_print_invert_without_color() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        assert_eq!(
+            print(args(["cal", "--invert"]), current_date).unwrap(),
+            print(args(["cal"]), current_date).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_print_day_pad_zero() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--day-pad", "zero"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             March 2024     
+        Mo Tu We Th Fr Sa Su
+                    01 02 03
+        04 05 06 07 08 09 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29 30 31
+        "###);
+    }
+
+    #[test]
+    fn test_print_day_pad_left() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--day-pad", "left"]);
+
+        insta::assert_snapshot!(show_edges(&print(args, current_date).unwrap()), @r###"
+        |     March 2024     |
+        |Mo Tu We Th Fr Sa Su|
+        |            1  2  3 |
+        |4  5  6  7  8  9  10|
+        |11 12 13 14 15 16 17|
+        |18 19 20 21 22 23 24|
+        |25 26 27 28 29 30 31|
+        "###);
+    }
+
+    #[test]
+    fn test_month_range_summary() {
+        let months = build_month_range(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            Weekday::Mon,
+        );
+
+        assert_eq!(
+            months.summary(&BTreeMap::new()),
+            RangeSummary {
+                days: 91,
+                weekend_days: 26,
+                holidays: None,
+            }
+        );
+
+        // a holiday on a weekend doesn't take away a business day
+        let holidays = BTreeMap::from([
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                "New Year".to_string(),
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 3, 30).unwrap(),
+                "Saturday".to_string(),
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 4, 1).unwrap(),
+                "Outside".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            months.summary(&holidays),
+            RangeSummary {
+                days: 91,
+                weekend_days: 26,
+                holidays: Some(HolidaySummary {
+                    holidays: 2,
+                    business_days: 64,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_print_range_summary() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let march = args(["cal", "--range-summary"]);
+
+        insta::assert_snapshot!(print(march, current_date).unwrap(), @r###"
+             March 2024     
+        Mo Tu We Th Fr Sa Su
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29 30 31
+
+        31 days, 10 weekend days
+        "###);
+
+        // Independence Day and Labor Day fall on weekdays
+        let output = print(
+            args([
+                "cal",
+                "--range-summary",
+                "--holiday-set",
+                "us-federal",
+                "2024-Q3",
+            ]),
+            current_date,
+        )
+        .unwrap();
+        assert_eq!(
+            output.lines().last().unwrap(),
+            "92 days, 26 weekend days, 2 holidays, 64 business days"
+        );
+    }
+
+    #[test]
+    fn test_print_range_summary_fiscal() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let summary = |argv: &[&str]| {
+            let output = print(args(argv.iter().copied()), current_date).unwrap();
+
+            output.lines().last().unwrap().to_string()
+        };
+
+        assert_eq!(
+            summary(&["cal", "--fiscal", "--range-summary", "2024-03"]),
+            "31 days, 10 weekend days (Q3 FY2024)"
+        );
+        assert_eq!(
+            summary(&["cal", "--fiscal", "--range-summary", "2024-06", "-A", "1"]),
+            "61 days, 18 weekend days (Q4 FY2024 - Q1 FY2025)"
+        );
+        assert_eq!(
+            summary(&[
+                "cal",
+                "--fiscal",
+                "--fiscal-year-start",
+                "10",
+                "--range-summary",
+                "2024-03"
+            ]),
+            "31 days, 10 weekend days (Q2 FY2024)"
+        );
+        assert_eq!(
+            summary(&["cal", "--range-summary", "2024-03"]),
+            "31 days, 10 weekend days"
+        );
+    }
+
+    #[test]
+    fn test_parse_month_input() {
+        assert_eq!(
+            parse_month_input("2024-03"),
+            Ok(DateInput::YearMonth(
+                Year {
+                    style: YearStyle::Calendar,
+                    year: 2024
+                },
+                3
+            ))
+        );
+        assert_eq!(
+            parse_month_input("2024Q1"),
+            Err("Expected a specific month (e.g. 2024-03), got: 2024Q1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_print_diff() {
+        let _color = force_color("0");
+
+        let args = args(["cal", "-f", "sunday", "--diff", "2024-03", "2024-04"]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           March 2024 (Fri) vs April 2024 (Mon)   
+
+             March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                        1  2      1  2  3  4  5  6
+         3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        24 25 26 27 28 29 30  28 29 30            
+        31                                        
+        "###);
+    }
+
+    #[test]
+    fn test_print_themes() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let print_theme = |theme| {
+            let args = args([
+                "cal",
+                "-f",
+                "sunday",
+                "--highlight-nth-weekday",
+                "1:thu",
+                "--theme",
+                theme,
+            ]);
+
+            escape_ansi(&print(args, current_date).unwrap())
+        };
+
+        insta::assert_snapshot!(print_theme("default"), @r###"
+           February 2024    
+        Su Mo Tu We Th Fr Sa
+                    \e[4m 1\e[24m  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 \e[7m20\e[27m 21 22 23 24
+        25 26 27 28 29      
+        "###);
+        insta::assert_snapshot!(print_theme("solarized"), @r###"
+           \e[1;33mFebruary 2024\e[22;39m    
+        \e[36mSu Mo Tu We Th Fr Sa\e[39m
+                    \e[4;35m 1\e[24;39m  2 \e[34m 3\e[39m
+        \e[34m 4\e[39m  5  6  7  8  9 \e[34m10\e[39m
+        \e[34m11\e[39m 12 13 14 15 16 \e[34m17\e[39m
+        \e[34m18\e[39m 19 \e[7;33m20\e[27;39m 21 22 23 \e[34m24\e[39m
+        \e[34m25\e[39m 26 27 28 29      
+        "###);
+        insta::assert_snapshot!(print_theme("mono"), @r###"
+           \e[1mFebruary 2024\e[22m    
+        \e[2mSu Mo Tu We Th Fr Sa\e[22m
+                    \e[4m 1\e[24m  2 \e[2m 3\e[22m
+        \e[2m 4\e[22m  5  6  7  8  9 \e[2m10\e[22m
+        \e[2m11\e[22m 12 13 14 15 16 \e[2m17\e[22m
+        \e[2m18\e[22m 19 \e[7m20\e[27m 21 22 23 \e[2m24\e[22m
+        \e[2m25\e[22m 26 27 28 29      
+        "###);
+        insta::assert_snapshot!(print_theme("high-contrast"), @r###"
+           \e[1;97mFebruary 2024\e[22;39m    
+        \e[1mSu Mo Tu We Th Fr Sa\e[22m
+                    \e[1;4m 1\e[22;24m  2 \e[93m 3\e[39m
+        \e[93m 4\e[39m  5  6  7  8  9 \e[93m10\e[39m
+        \e[93m11\e[39m 12 13 14 15 16 \e[93m17\e[39m
+        \e[93m18\e[39m 19 \e[1;7m20\e[22;27m 21 22 23 \e[93m24\e[39m
+        \e[93m25\e[39m 26 27 28 29      
+        "###);
+    }
+
+    #[test]
+    fn test_print_highlight_style() {
+        let _color = force_color("1");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let print_style = |style| {
+            let args = args(["cal", "-f", "sunday", "--highlight-style", style]);
+
+            escape_ansi(&print(args, current_date).unwrap())
+        };
+
+        insta::assert_snapshot!(print_style("underline"), @r###"
+           February 2024    
+        Su Mo Tu We Th Fr Sa
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 \e[4m20\e[24m 21 22 23 24
+        25 26 27 28 29      
+        "###);
+        insta::assert_snapshot!(print_style("bold"), @r###"
+           February 2024    
+        Su Mo Tu We Th Fr Sa
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 \e[1m20\e[22m 21 22 23 24
+        25 26 27 28 29      
+        "###);
+        insta::assert_snapshot!(show_edges(&print_style("box")), @r###"
+        |    February 2024     |
+        | Su Mo Tu We Th Fr Sa |
+        |              1  2  3 |
+        |  4  5  6  7  8  9 10 |
+        | 11 12 13 14 15 16 17 |
+        | 18 19[20]21 22 23 24 |
+        | 25 26 27 28 29       |
+        "###);
+    }
+
+    #[test]
+    fn test_print_repeat() {
+        let _color = force_color("0");
+
+        let repeated = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "--repeat",
+            "3",
+            "--columns",
+            "2",
+        ]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(print(repeated, current_date).unwrap(), @r###"
+           February 2024         February 2024    
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3               1  2  3
+         4  5  6  7  8  9 10   4  5  6  7  8  9 10
+        11 12 13 14 15 16 17  11 12 13 14 15 16 17
+        18 19 20 21 22 23 24  18 19 20 21 22 23 24
+        25 26 27 28 29        25 26 27 28 29      
+
+           February 2024    
+        Su Mo Tu We Th Fr Sa
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29      
+        "###);
+
+        // every copy stays aligned to its first day
+        let aligned = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "1",
+            "--align-firsts",
+            "--repeat",
+            "2",
+            "--columns",
+            "2",
+        ]);
+
+        insta::assert_snapshot!(print(aligned, current_date).unwrap(), @r###"
+           February 2024         February 2024    
+        Th Fr Sa Su Mo Tu We  Th Fr Sa Su Mo Tu We
+         1  2  3  4  5  6  7   1  2  3  4  5  6  7
+         8  9 10 11 12 13 14   8  9 10 11 12 13 14
+        15 16 17 18 19 20 21  15 16 17 18 19 20 21
+        22 23 24 25 26 27 28  22 23 24 25 26 27 28
+        29                    29                  
+
+             March 2024            March 2024     
+        Fr Sa Su Mo Tu We Th  Fr Sa Su Mo Tu We Th
+         1  2  3  4  5  6  7   1  2  3  4  5  6  7
+         8  9 10 11 12 13 14   8  9 10 11 12 13 14
+        15 16 17 18 19 20 21  15 16 17 18 19 20 21
+        22 23 24 25 26 27 28  22 23 24 25 26 27 28
+        29 30 31              29 30 31            
+        "###);
+    }
+
+    #[test]
+    fn test_print_fill_empty() {
+        let _color = force_color("0");
+
+        // February has one fewer week than March, the row below it stays blank
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "1",
+            "--fill-empty",
+            "·",
+        ]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(show_edges(&print(args, current_date).unwrap()), @r###"
+        |   February 2024           March 2024     |
+        |Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa|
+        |·· ·· ·· ··  1  2  3  ·· ·· ·· ·· ··  1  2|
+        | 4  5  6  7  8  9 10   3  4  5  6  7  8  9|
+        |11 12 13 14 15 16 17  10 11 12 13 14 15 16|
+        |18 19 20 21 22 23 24  17 18 19 20 21 22 23|
+        |25 26 27 28 29 ·· ··  24 25 26 27 28 29 30|
+        |                      31 ·· ·· ·· ·· ·· ··|
+        "###);
+    }
+
+    #[test]
+    fn test_print_trailing_days_from_adjacent_months() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let adjacent = || {
+            args([
+                "cal",
+                "-f",
+                "sunday",
+                "2024-02",
+                "-A",
+                "1",
+                "--trailing-days-from-adjacent-months",
+            ])
+        };
+
+        let plain = {
+            let _color = force_color("0");
+            print(adjacent(), current_date).unwrap()
+        };
+        insta::assert_snapshot!(show_edges(&plain), @r###"
+        |    February 2024             March 2024      |
+        | Su Mo Tu We Th Fr Sa    Su Mo Tu We Th Fr Sa |
+        |(28 29 30 31) 1  2  3   (25 26 27 28 29) 1  2 |
+        |  4  5  6  7  8  9 10     3  4  5  6  7  8  9 |
+        | 11 12 13 14 15 16 17    10 11 12 13 14 15 16 |
+        | 18 19 20 21 22 23 24    17 18 19 20 21 22 23 |
+        | 25 26 27 28 29( 1  2)   24 25 26 27 28 29 30 |
+        |                         31( 1  2  3  4  5  6)|
+        "###);
+
+        let color = {
+            let _color = force_color("1");
+            print(adjacent(), current_date).unwrap()
+        };
+        insta::assert_snapshot!(escape_ansi(&color), @r###"
+           February 2024           March 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+        \e[2m28\e[22m \e[2m29\e[22m \e[2m30\e[22m \e[2m31\e[22m  1  2  3  \e[2m25\e[22m \e[2m26\e[22m \e[2m27\e[22m \e[2m28\e[22m \e[2m29\e[22m  1  2
+         4  5  6  7  8  9 10   3  4  5  6  7  8  9
+        11 12 13 14 15 16 17  10 11 12 13 14 15 16
+        18 19 \e[7m20\e[27m 21 22 23 24  17 18 19 20 21 22 23
+        25 26 27 28 29 \e[2m 1\e[22m \e[2m 2\e[22m  24 25 26 27 28 29 30
+                              31 \e[2m 1\e[22m \e[2m 2\e[22m \e[2m 3\e[22m \e[2m 4\e[22m \e[2m 5\e[22m \e[2m 6\e[22m
+        "###);
+    }
+
+    #[test]
+    fn test_holiday_set() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let holidays = |set: HolidaySet, year| {
+            set.holidays(year)
+                .into_iter()
+                .map(|(date, name)| format!("{}  {}", date, name))
+                .collect_vec()
+        };
+
+        assert_eq!(
+            holidays(HolidaySet::UsFederal, 2024),
+            [
+                "2024-01-01  New Year's Day",
+                "2024-01-15  Martin Luther King Jr. Day",
+                "2024-02-19  Washington's Birthday",
+                "2024-05-27  Memorial Day",
+                "2024-06-19  Juneteenth",
+                "2024-07-04  Independence Day",
+                "2024-09-02  Labor Day",
+                "2024-10-14  Columbus Day",
+                "2024-11-11  Veterans Day",
+                "2024-11-28  Thanksgiving Day",
+                "2024-12-25  Christmas Day",
+            ]
+        );
+
+        // a Saturday holiday is observed on Friday, a Sunday one on Monday
+        let us_2022 = HolidaySet::UsFederal.holidays(2022);
+        assert_eq!(us_2022[&date(2021, 12, 31)], "New Year's Day (observed)");
+        assert_eq!(us_2022[&date(2022, 6, 20)], "Juneteenth (observed)");
+        assert_eq!(us_2022[&date(2022, 12, 26)], "Christmas Day (observed)");
+        assert!(!HolidaySet::UsFederal
+            .holidays(2020)
+            .values()
+            .any(|name| name == "Juneteenth"));
+
+        assert_eq!(
+            holidays(HolidaySet::UkBank, 2024),
+            [
+                "2024-01-01  New Year's Day",
+                "2024-03-29  Good Friday",
+                "2024-04-01  Easter Monday",
+                "2024-05-06  Early May bank holiday",
+                "2024-05-27  Spring bank holiday",
+                "2024-08-26  Summer bank holiday",
+                "2024-12-25  Christmas Day",
+                "2024-12-26  Boxing Day",
+            ]
+        );
+
+        // substitute days go to the next weekday that isn't already a holiday
+        let uk_2021 = HolidaySet::UkBank.holidays(2021);
+        assert_eq!(uk_2021[&date(2021, 12, 27)], "Christmas Day (observed)");
+        assert_eq!(uk_2021[&date(2021, 12, 28)], "Boxing Day (observed)");
+        let uk_2022 = HolidaySet::UkBank.holidays(2022);
+        assert_eq!(uk_2022[&date(2022, 12, 26)], "Boxing Day");
+        assert_eq!(uk_2022[&date(2022, 12, 27)], "Christmas Day (observed)");
+
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+
+    #[test]
+    fn test_print_list_holidays() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let list = |argv: &[&str]| print(args(argv.iter().copied()), current_date);
+
+        insta::assert_snapshot!(list(&["cal", "--list-holidays", "uk-bank", "2024"]).unwrap(), @r###"
+        2024-01-01  New Year's Day
+        2024-03-29  Good Friday
+        2024-04-01  Easter Monday
+        2024-05-06  Early May bank holiday
+        2024-05-27  Spring bank holiday
+        2024-08-26  Summer bank holiday
+        2024-12-25  Christmas Day
+        2024-12-26  Boxing Day
+        "###);
+        // the whole year is listed, even when only part of it is displayed
+        assert_eq!(
+            list(&["cal", "--list-holidays", "uk-bank"]),
+            list(&["cal", "--list-holidays", "uk-bank", "2024"])
+        );
+        insta::assert_snapshot!(
+            list(&["cal", "--list-holidays", "us-federal", "2024-07", "--format", "json"]).unwrap(),
+            @r###"
+        [{"date":"2024-01-01","name":"New Year's Day"},{"date":"2024-01-15","name":"Martin Luther King Jr. Day"},{"date":"2024-02-19","name":"Washington's Birthday"},{"date":"2024-05-27","name":"Memorial Day"},{"date":"2024-06-19","name":"Juneteenth"},{"date":"2024-07-04","name":"Independence Day"},{"date":"2024-09-02","name":"Labor Day"},{"date":"2024-10-14","name":"Columbus Day"},{"date":"2024-11-11","name":"Veterans Day"},{"date":"2024-11-28","name":"Thanksgiving Day"},{"date":"2024-12-25","name":"Christmas Day"}]
+        "###
+        );
+        assert!(matches!(
+            list(&["cal", "--list-holidays", "us-federal", "--format", "svg"]),
+            Err(CalError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_print_holiday_set() {
+        let _color = force_color("0");
+
+        let config = Config::parse(
+            r#"
+            [holidays]
+            "2024-07-04" = "Fireworks"
+            "2024-07-19" = "Company Picnic"
+            "#,
+        )
+        .unwrap();
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-06",
+            "-A",
+            "1",
+            "--holiday-set",
+            "us-federal",
+        ])
+        .with_config(&config);
+
+        // the config file's name wins for the same date
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             June 2024             July 2024      
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                           1      1  2  3  4  5  6
+         2  3  4  5  6  7  8   7  8  9 10 11 12 13
+         9 10 11 12 13 14 15  14 15 16 17 18 19 20
+        16 17 18 19 20 21 22  21 22 23 24 25 26 27
+        23 24 25 26 27 28 29  28 29 30 31         
+        30                                        
+
+        2024-06-19  Juneteenth
+        2024-07-04  Fireworks
+        2024-07-19  Company Picnic
+        "###);
+    }
+
+    #[test]
+    fn test_print_week_of_holiday_set() {
+        let _color = force_color("1");
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let week_of = |date: &str| {
+            let args = args([
+                "cal",
+                "-f",
+                "monday",
+                "--week-of",
+                date,
+                "--holiday-set",
+                "uk-bank",
+                "--theme",
+                "mono",
+            ]);
+
+            escape_ansi(&print(args, current_date).unwrap())
+        };
+
+        // the substitute Christmas and Boxing Day bank holidays are marked either way
+        assert_eq!(week_of("2021-12-29"), week_of("2022-01-02"));
+        insta::assert_snapshot!(week_of("2022-01-02"), @r###"
+        \e[2mMo Tu We Th Fr Sa Su\e[22m
+        \e[1m27\e[22m \e[1m28\e[22m 29 30 31 \e[2m 1\e[22m \e[2m 2\e[22m
+        "###);
+    }
+
+    #[test]
+    fn test_print_holiday_legend() {
+        let config = Config::parse(
+            r#"
+            [holidays]
+            "2024-07-04" = "Independence Day"
+            "2024-07-19" = "Company Picnic"
+            "2024-12-25" = "Christmas"
+            "#,
+        )
+        .unwrap();
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let july = || {
+            args(["cal", "-f", "sunday", "2024-07", "--theme", "solarized"]).with_config(&config)
+        };
+
+        let plain = {
+            let _color = force_color("0");
+            print(july(), current_date).unwrap()
+        };
+        insta::assert_snapshot!(plain, @r###"
+             July 2024      
+        Su Mo Tu We Th Fr Sa
+            1  2  3  4  5  6
+         7  8  9 10 11 12 13
+        14 15 16 17 18 19 20
+        21 22 23 24 25 26 27
+        28 29 30 31         
+
+        2024-07-04  Independence Day
+        2024-07-19  Company Picnic
+        "###);
+
+        let color = {
+            let _color = force_color("1");
+            print(july(), current_date).unwrap()
+        };
+        insta::assert_snapshot!(escape_ansi(&color), @r###"
+             \e[1;33mJuly 2024\e[22;39m      
+        \e[36mSu Mo Tu We Th Fr Sa\e[39m
+            1  2  3 \e[31m 4\e[39m  5 \e[34m 6\e[39m
+        \e[34m 7\e[39m  8  9 10 11 12 \e[34m13\e[39m
+        \e[34m14\e[39m 15 16 17 18 \e[31m19\e[39m \e[34m20\e[39m
+        \e[34m21\e[39m 22 23 24 25 26 \e[34m27\e[39m
+        \e[34m28\e[39m 29 30 31         
+
+        \e[31m2024-07-04\e[39m  Independence Day
+        \e[31m2024-07-19\e[39m  Company Picnic
+        "###);
+
+        let svg = print(
+            args(["cal", "2024-07", "--format", "svg"]).with_config(&config),
+            current_date,
+        )
+        .unwrap();
+        assert!(!svg.contains("Independence Day"));
+        assert!(svg.contains(r#"class="day holiday""#));
+
+        assert_eq!(
+            Config::parse("[holidays]\n\"12-25\" = \"Christmas\""),
+            Err("holidays must be dated YYYY-MM-DD, got: 12-25".to_string())
+        );
+    }
+
+    #[test]
+    fn test_debug_leaves_output_alone() {
+        let _color = force_color("0");
+
+        // the trace is written to stderr by the subscriber set up in `main`
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        assert_eq!(
+            print(args(["cal", "--debug", "24Q1"]), current_date),
+            print(args(["cal", "24Q1"]), current_date)
+        );
+    }
+
+    #[test]
+    fn test_quiet() {
+        let _color = force_color("0");
+
+        let config = Config::parse(
+            r#"
+            [holidays]
+            "2024-07-04" = "Independence Day"
+            "#,
+        )
+        .unwrap();
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let july = |quiet: bool| {
+            let mut argv = vec![
+                "cal",
+                "-f",
+                "sunday",
+                "2024-07",
+                "--force-weeks",
+                "4",
+                "--day-gap",
+                "thin",
+            ];
+            if quiet {
+                argv.push("--quiet");
+            }
+            let mut notices = Vec::new();
+            let output = print_with_notices(
+                args(argv).with_config(&config),
+                current_date,
+                &mut |message| notices.push(message),
+            )
+            .unwrap();
+
+            (output, notices)
+        };
+
+        let (loud, notices) = july(false);
+        assert_eq!(notices.len(), 2);
+        assert!(notices[0].starts_with("warning: `--day-gap thin` is experimental"));
+        assert_eq!(
+            notices[1],
+            "warning: `--force-weeks 4` hides 4 day(s) that don't fit in 4 week(s)."
+        );
+
+        // the notices are the only difference
+        let (quiet, notices) = july(true);
+        assert_eq!(notices, Vec::<String>::new());
+        assert_eq!(quiet, loud);
+        assert!(quiet.contains("Independence Day"));
+    }
+
+    #[test]
+    fn test_print_use_tabs() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let output = print(
+            args(["cal", "-f", "sunday", "2024-06", "-A", "1", "--use-tabs"]),
+            current_date,
+        )
+        .unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "     June 2024      \t     July 2024      ");
+        assert_eq!(lines[1], "Su Mo Tu We Th Fr Sa\tSu Mo Tu We Th Fr Sa");
+        assert_eq!(lines[2], "                   1\t    1  2  3  4  5  6");
+        assert!(lines.iter().all(|line| line.split('\t').count() == 2));
+
+        assert!(Arguments::try_parse_from(["cal", "--use-tabs", "--quarter-dividers"]).is_err());
+    }
+
+    #[test]
+    fn test_pad_to_width() {
+        let center = |text| pad_to_width(text, 20, fmt::Alignment::Center);
+
+        // matches `{:^20}` for ASCII
+        assert_eq!(center("March 2024"), format!("{:^20}", "March 2024"));
+        assert_eq!(center("May 2024"), format!("{:^20}", "May 2024"));
+
+        // each CJK character takes two columns, so there's less padding than `{:^20}` adds
+        assert_eq!(center("三月 2024"), "     三月 2024      ");
+        assert_eq!(center("三月 2024").width(), 20);
+
+        // combining accents take no columns of their own
+        assert_eq!(center("fe\u{301}vrier 2024"), "    fe\u{301}vrier 2024    ");
+        assert_eq!(center("fe\u{301}vrier 2024").width(), 20);
+
+        assert_eq!(pad_to_width(WEEKEND_EMOJI, 3, fmt::Alignment::Right), " 🎉");
+        assert_eq!(pad_to_width(WEEKEND_EMOJI, 3, fmt::Alignment::Left), "🎉 ");
+        // text that's already too wide is left as is
+        assert_eq!(
+            pad_to_width("September 2024", 4, fmt::Alignment::Center),
+            "September 2024"
+        );
+    }
+
+    #[test]
+    fn test_print_emoji_weekends() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let output = print(
+            args(["cal", "-f", "monday", "2024-03", "--emoji-weekends"]),
+            current_date,
+        )
+        .unwrap();
+
+        insta::assert_snapshot!(output, @r###"
+             March 2024     
+        Mo Tu We Th Fr Sa Su
+                     1 🎉 🎉
+         4  5  6  7  8 🎉 🎉
+        11 12 13 14 15 🎉 🎉
+        18 19 20 21 22 🎉 🎉
+        25 26 27 28 29 🎉 🎉
+        "###);
+
+        // the emoji fill the same number of columns as the day numbers they replace
+        assert_eq!(WEEKEND_EMOJI.width(), 2);
+        for argv in [
+            vec!["cal", "-A", "1", "--emoji-weekends"],
+            vec!["cal", "--emoji-weekends", "--cell-content", "day-of-year"],
+            vec![
+                "cal",
+                "--emoji-weekends",
+                "--day-pad",
+                "left",
+                "--ascii-today-marker",
+            ],
+            vec![
+                "cal",
+                "-f",
+                "monday",
+                "--emoji-weekends",
+                "--fold-weekends-into-one-column",
+            ],
+        ] {
+            let output = print(args(argv.clone()), current_date).unwrap();
+            let widths: Vec<usize> = output.lines().map(|line| line.width()).collect();
+
+            assert!(widths.iter().all_equal(), "{:?}: {:?}", argv, widths);
+        }
+    }
+
+    #[test]
+    fn test_print_iso_week_year_in_header() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let argv = |month| {
+            args([
+                "cal",
+                "-f",
+                "monday",
+                month,
+                "--append-weeknum-suffix",
+                "--show-iso-week-year-in-header",
+            ])
+        };
+
+        // December 30th and 31st, 2024 are in the first ISO week of 2025
+        insta::assert_snapshot!(show_edges(&print(argv("2024-12"), current_date).unwrap()), @r###"
+        | Dec 2024 / 2025-W01  |
+        | Mo Tu We Th Fr Sa Su |
+        |                    1 |
+        |W49  3  4  5  6  7  8 |
+        |W50 10 11 12 13 14 15 |
+        |W51 17 18 19 20 21 22 |
+        |W52 24 25 26 27 28 29 |
+        |W01 31                |
+        "###);
+
+        // January 1st - 3rd, 2021 are in the last ISO week of 2020
+        let january = print(argv("2021-01"), current_date).unwrap();
+        assert_eq!(january.lines().next(), Some(" Jan 2021 / 2020-W53  "));
+
+        // every day of June is in a 2024 ISO week
+        let june = print(argv("2024-06"), current_date).unwrap();
+        assert_eq!(june.lines().next(), Some("      June 2024       "));
+    }
+
+    #[test]
+    fn test_print_fold_weekends() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let folded = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-06",
+            "-A",
+            "1",
+            "--fold-weekends-into-one-column",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(folded, current_date).unwrap()), @r###"
+        |     June 2024             July 2024      |
+        |Mo Tu We Th Fr Sa/Su  Mo Tu We Th Fr Sa/Su|
+        |                1/ 2   1  2  3  4  5  6/ 7|
+        | 3  4  5  6  7  8/ 9   8  9 10 11 12 13/14|
+        |10 11 12 13 14 15/16  15 16 17 18 19 20/21|
+        |17 18 19 20 21 22/23  22 23 24 25 26 27/28|
+        |24 25 26 27 28 29/30  29 30 31            |
+        "###);
+
+        // today's marker brackets the whole weekend
+        let marked = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-06",
+            "--fold-weekends-into-one-column",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(show_edges(&print(marked, current_date).unwrap()), @r###"
+        |      June 2024       |
+        | Mo Tu We Th Fr Sa/Su |
+        |               [ 1/ 2]|
+        |  3  4  5  6  7  8/ 9 |
+        | 10 11 12 13 14 15/16 |
+        | 17 18 19 20 21 22/23 |
+        | 24 25 26 27 28 29/30 |
+        "###);
+
+        assert_eq!(
+            print(
+                args(["cal", "-f", "sunday", "--fold-weekends-into-one-column"]),
+                current_date
+            ),
+            Err(CalError::IncompatibleOptions(
+                "--fold-weekends-into-one-column needs weeks that start on Monday".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_print_continuous() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let continuous = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "2",
+            "--continuous",
+            "--ascii-today-marker",
+        ]);
+
+        insta::assert_snapshot!(print(continuous, current_date).unwrap(), @r###"
+                  Su Mo Tu We Th Fr Sa 
+        Feb 2024               1  2  3 
+                   4  5  6  7  8  9 10 
+                  11 12 13 14 15 16 17 
+                  18 19 20 21 22 23 24 
+        Mar 2024  25 26 27 28 29  1  2 
+                   3  4  5  6  7  8  9 
+                  10 11 12 13 14 15 16 
+                  17 18 19[20]21 22 23 
+                  24 25 26 27 28 29 30 
+        Apr 2024  31  1  2  3  4  5  6 
+                   7  8  9 10 11 12 13 
+                  14 15 16 17 18 19 20 
+                  21 22 23 24 25 26 27 
+                  28 29 30             
+        "###);
+
+        // a range starting mid-month is labeled on its first row
+        let mid_month = args([
+            "cal",
+            "-f",
+            "monday",
+            "2024-06-12..2024-07-10",
+            "--continuous",
+            "--header-rule",
+            "dash",
+        ]);
+
+        insta::assert_snapshot!(print(mid_month, current_date).unwrap(), @r###"
+                 Mo Tu We Th Fr Sa Su
+                 --------------------
+        Jun 2024       12 13 14 15 16
+                 17 18 19 20 21 22 23
+                 24 25 26 27 28 29 30
+        Jul 2024  1  2  3  4  5  6  7
+                  8  9 10            
+        "###);
+
+        assert_eq!(
+            print(
+                args(["cal", "--continuous", "--format", "svg"]),
+                current_date
+            ),
+            Err(CalError::UnsupportedFormat(
+                "--continuous can't be printed as svg, only as text".to_string()
+            ))
+        );
+        assert!(Arguments::try_parse_from(["cal", "--continuous", "--columns", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_print_week_of() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        let sunday_first = args(["cal", "-f", "sunday", "--week-of", "2024-03-20"]);
+        insta::assert_snapshot!(print(sunday_first, current_date).unwrap(), @r###"
+        Su Mo Tu We Th Fr Sa
+        17 18 19 20 21 22 23
+        "###);
+
+        // spans the end of February and the start of March
+        let month_boundary = args(["cal", "-f", "monday", "--week-of", "2024-03-01"]);
+        insta::assert_snapshot!(print(month_boundary, current_date).unwrap(), @r###"
+        Mo Tu We Th Fr Sa Su
+        26 27 28 29  1  2  3
+        "###);
+    }
+
+    #[test]
+    fn test_print_show_day_count_per_month() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let counted = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "1",
+            "--show-day-count-per-month",
+        ]);
+
+        insta::assert_snapshot!(print(counted, current_date).unwrap(), @r###"
+         February 2024 (29)     March 2024 (31)   
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3                  1  2
+         4  5  6  7  8  9 10   3  4  5  6  7  8  9
+        11 12 13 14 15 16 17  10 11 12 13 14 15 16
+        18 19 20 21 22 23 24  17 18 19 20 21 22 23
+        25 26 27 28 29        24 25 26 27 28 29 30
+                              31                  
+        "###);
+
+        // `2024-02 (Feb 2024) (29)` would be wider than the month
+        let both = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "--header-format",
+            "both",
+            "--show-day-count-per-month",
+        ]);
+
+        insta::assert_snapshot!(print(both, current_date).unwrap(), @r###"
+         2024-02 (Feb 2024) 
+        Su Mo Tu We Th Fr Sa
+                     1  2  3
+         4  5  6  7  8  9 10
+        11 12 13 14 15 16 17
+        18 19 20 21 22 23 24
+        25 26 27 28 29      
+        "###);
+    }
+
+    #[test]
+    fn test_print_header_format() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let print_header_format = |header_format| {
+            let args = args([
+                "cal",
+                "-f",
+                "sunday",
+                "2024-08",
+                "-A",
+                "1",
+                "--header-format",
+                header_format,
+            ]);
+
+            print(args, current_date).unwrap()
+        };
+
+        insta::assert_snapshot!(print_header_format("name"), @r###"
+            August 2024          September 2024   
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3   1  2  3  4  5  6  7
+         4  5  6  7  8  9 10   8  9 10 11 12 13 14
+        11 12 13 14 15 16 17  15 16 17 18 19 20 21
+        18 19 20 21 22 23 24  22 23 24 25 26 27 28
+        25 26 27 28 29 30 31  29 30               
+        "###);
+        insta::assert_snapshot!(print_header_format("iso"), @r###"
+              2024-08               2024-09       
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3   1  2  3  4  5  6  7
+         4  5  6  7  8  9 10   8  9 10 11 12 13 14
+        11 12 13 14 15 16 17  15 16 17 18 19 20 21
+        18 19 20 21 22 23 24  22 23 24 25 26 27 28
+        25 26 27 28 29 30 31  29 30               
+        "###);
+        insta::assert_snapshot!(print_header_format("both"), @r###"
+         2024-08 (Aug 2024)    2024-09 (Sep 2024) 
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3   1  2  3  4  5  6  7
+         4  5  6  7  8  9 10   8  9 10 11 12 13 14
+        11 12 13 14 15 16 17  15 16 17 18 19 20 21
+        18 19 20 21 22 23 24  22 23 24 25 26 27 28
+        25 26 27 28 29 30 31  29 30               
+        "###);
+    }
+
+    #[test]
+    fn test_print_align_firsts() {
+        let _color = force_color("0");
+
+        let args = args([
+            "cal",
+            "-f",
+            "sunday",
+            "2024-02",
+            "-A",
+            "1",
+            "--align-firsts",
+        ]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           February 2024           March 2024     
+        Th Fr Sa Su Mo Tu We  Fr Sa Su Mo Tu We Th
+         1  2  3  4  5  6  7   1  2  3  4  5  6  7
+         8  9 10 11 12 13 14   8  9 10 11 12 13 14
+        15 16 17 18 19 20 21  15 16 17 18 19 20 21
+        22 23 24 25 26 27 28  22 23 24 25 26 27 28
+        29                    29 30 31            
+        "###);
+    }
+
+    #[test]
+    fn test_print_theme_ignored_without_color() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        assert_eq!(
+            print(args(["cal", "--theme", "solarized"]), current_date).unwrap(),
+            print(args(["cal"]), current_date).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_print_weekday_of() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        assert_eq!(
+            print(args(["cal", "--weekday-of", "2024-03-01"]), current_date),
+            Ok("Friday".to_string())
+        );
+        assert_eq!(
+            print(args(["cal", "--weekday-of", "2024-09"]), current_date),
+            Ok("Sunday".to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--weekday-of", "2024-03-01", "--format", "json"]),
+                current_date
+            ),
+            Ok(r#"{"date":"2024-03-01","weekday":"Friday","iso_weekday":5}"#.to_string())
+        );
+        assert!(Arguments::try_parse_from(["cal", "--weekday-of", "2024Q1"]).is_err());
+        assert_eq!(
+            print(args(["cal", "--format", "json"]), current_date),
+            Err(CalError::UnsupportedFormat(
+                "calendars can't be printed as json, only as text, svg or grid-csv".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_version_verbose() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let output = print(args(["cal", "--version-verbose"]), current_date).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], format!("cal {}", env!("CARGO_PKG_VERSION")));
+        assert!(lines[1].starts_with("commit: "));
+        assert!(lines[2].starts_with("build date: "));
+        assert_eq!(lines[3], format!("target: {}", env!("CAL_TARGET")));
+
+        let version = Arguments::command().render_version();
+        assert_eq!(version.trim_end(), lines[0]);
+    }
+
+    #[test]
+    fn test_print_weekday_counts() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let count = |argv: &[&str]| print(args(argv.iter().copied()), current_date);
+
+        // Q3 2024 runs from Monday, July 1st through Monday, September 30th
+        assert_eq!(
+            count(&["cal", "2024Q3", "--count-weekday", "fri"]),
+            Ok("13".to_string())
+        );
+        assert_eq!(
+            count(&["cal", "2024Q3", "--count-weekday", "Monday"]),
+            Ok("14".to_string())
+        );
+        assert_eq!(
+            count(&[
+                "cal",
+                "2024Q3",
+                "--count-weekday",
+                "mon",
+                "--count-weekday",
+                "fri",
+                "--count-weekday",
+                "mon"
+            ]),
+            Ok("Monday: 14\nFriday: 13".to_string())
+        );
+        assert_eq!(
+            count(&[
+                "cal",
+                "2024Q3",
+                "--count-weekday",
+                "mon",
+                "--count-weekday",
+                "fri",
+                "--format",
+                "json"
+            ]),
+            Ok(r#"{"friday":13,"monday":14}"#.to_string())
+        );
+
+        // overlapping ranges don't count the same day twice
+        assert_eq!(
+            count(&["cal", "2024-07", "2024Q3", "--count-weekday", "fri"]),
+            Ok("13".to_string())
+        );
+
+        assert_eq!(
+            count(&["cal", "2024Q3", "--count-weekday", "fri", "--format", "svg"]),
+            Err(CalError::UnsupportedFormat(
+                "--count-weekday can't be printed as svg, only as text or json".to_string()
+            ))
+        );
+        assert!(Arguments::try_parse_from(["cal", "--count-weekday", "someday"]).is_err());
+    }
+
+    #[test]
+    fn test_default_year_style() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let range = |argv: &[&str], config: &Config| {
+            let args = args(["cal", "--print-range"].iter().chain(argv).copied());
+
+            print(args.with_config(config), current_date).unwrap()
+        };
+        let fiscal = Config::parse("default_year_style = \"fiscal\"").unwrap();
+        let calendar = Config::default();
+
+        assert_eq!(range(&["Q1"], &calendar), "2024-01-01..2024-03-31");
+        assert_eq!(range(&["Q1"], &fiscal), "2023-07-01..2023-09-30");
+        assert_eq!(range(&["2024Q1"], &fiscal), "2023-07-01..2023-09-30");
+        assert_eq!(range(&["2024Q1-Q2"], &fiscal), "2023-07-01..2023-12-31");
+        assert_eq!(range(&["24"], &fiscal), "2023-07-01..2024-06-30");
+        assert_eq!(range(&["Q4+1"], &fiscal), "2024-07-01..2024-09-30");
+        // FY prefixes and months aren't affected
+        assert_eq!(range(&["FY2024Q1"], &fiscal), "2023-07-01..2023-09-30");
+        assert_eq!(range(&["2024-03"], &fiscal), "2024-03-01..2024-03-31");
+
+        // the command line takes precedence over the config file
+        assert_eq!(
+            range(&["--default-year-style", "calendar", "Q1"], &fiscal),
+            "2024-01-01..2024-03-31"
+        );
+        assert_eq!(
+            range(&["--default-year-style", "fiscal", "Q1"], &calendar),
+            "2023-07-01..2023-09-30"
+        );
+
+        assert!(Config::parse("default_year_style = \"academic\"").is_err());
+    }
+
+    #[test]
+    fn test_print_range() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        assert_eq!(
+            print(args(["cal", "--print-range", "2024Q1"]), current_date),
+            Ok("2024-01-01..2024-03-31".to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--print-range", "-B", "1", "-A", "2"]),
+                current_date
+            ),
+            Ok("2024-02-01..2024-05-31".to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--print-date-range", "2024-01", "2024-06"]),
+                current_date
+            ),
+            Ok("2024-01-01..2024-01-31\n2024-06-01..2024-06-30".to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--print-range", "--format", "json", "2024"]),
+                current_date
+            ),
+            Ok(r#"{"start":"2024-01-01","end":"2024-12-31"}"#.to_string())
+        );
+        assert_eq!(
+            print(
+                args(["cal", "--print-range", "--format", "svg"]),
+                current_date
+            ),
+            Err(CalError::UnsupportedFormat(
+                "--print-range can't be printed as svg, only as text or json".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_is_leap_year() {
+        assert!(is_leap_year(2000));
+        assert!(!is_leap_year(1900));
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(2023));
+    }
+
+    #[test]
+    fn test_print_is_leap() {
+        let current_date = NaiveDate::from_ymd_opt(2023, 3, 20).unwrap();
+        let is_leap = |args: Arguments| {
+            (
+                args.leap_year_query(current_date),
+                print(args, current_date).unwrap(),
+            )
+        };
+
+        assert_eq!(
+            is_leap(args(["cal", "--is-leap", "2000"])),
+            (Some(true), "true".to_string())
+        );
+        assert_eq!(
+            is_leap(args(["cal", "--is-leap", "1900"])),
+            (Some(false), "false".to_string())
+        );
+        assert_eq!(
+            is_leap(args(["cal", "--is-leap", "2024"])),
+            (Some(true), "true".to_string())
+        );
+        assert_eq!(
+            is_leap(args(["cal", "--is-leap"])),
+            (Some(false), "false".to_string())
+        );
+        assert_eq!(
+            is_leap(args(["cal", "--is-leap", "2024", "--format", "json"])),
+            (Some(true), r#"{"leap":true,"year":2024}"#.to_string())
+        );
+        assert_eq!(args(["cal"]).leap_year_query(current_date), None);
+    }
+
+    #[test]
+    fn test_print_grid_csv() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let grid = |argv: &[&str]| print(args(argv.iter().copied()), current_date);
+
+        // March 2024 starts on a Friday, leaving five blank cells before the 1st
+        insta::assert_snapshot!(
+            grid(&["cal", "-f", "sunday", "2024-03", "-A", "1", "--format", "grid-csv"]).unwrap(),
+            @r###"
+        March 2024
+        Su,Mo,Tu,We,Th,Fr,Sa
+        ,,,,,1,2
+        3,4,5,6,7,8,9
+        10,11,12,13,14,15,16
+        17,18,19,20,21,22,23
+        24,25,26,27,28,29,30
+        31,,,,,,
+
+        April 2024
+        Su,Mo,Tu,We,Th,Fr,Sa
+        ,1,2,3,4,5,6
+        7,8,9,10,11,12,13
+        14,15,16,17,18,19,20
+        21,22,23,24,25,26,27
+        28,29,30,,,,
+        "###
+        );
+
+        assert_eq!(
+            grid(&["cal", "--continuous", "--format", "grid-csv"]),
+            Err(CalError::UnsupportedFormat(
+                "--continuous can't be printed as grid-csv, only as text".to_string()
+            ))
+        );
+        assert_eq!(
+            grid(&["cal", "--weekday-of", "2024-03-20", "--format", "grid-csv"]),
+            Err(CalError::UnsupportedFormat(
+                "--weekday-of can't be printed as grid-csv, only as text or json".to_string()
+            ))
+        );
+
+        // `--verify` checks the CSV like any other output
+        let csv = grid(&["cal", "-f", "sunday", "2024-03", "--format", "grid-csv"]).unwrap();
+        let hash = output_hash(&csv);
+        let verify = |hash: &str| {
+            grid(&[
+                "cal", "-f", "sunday", "2024-03", "--format", "grid-csv", "--verify", hash,
+            ])
+        };
+        assert_eq!(verify(&hash), Ok(csv));
+        assert_eq!(
+            verify("0000000000000000"),
+            Err(CalError::VerificationFailed {
+                expected: "0000000000000000".to_string(),
+                actual: hash
+            })
+        );
+    }
+
+    #[test]
+    fn test_print_svg() {
+        let args = args(["cal", "-f", "sunday", "2024-02", "--format", "svg"]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+        <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 196 160" class="calendar">
+          <style>
+            text { font-family: monospace; font-size: 12px; text-anchor: middle; }
+            .month-header { font-weight: bold; }
+            .today { text-decoration: underline; font-weight: bold; }
+            .holiday { font-style: italic; }
+          </style>
+          <g class="month" transform="translate(0, 0)">
+            <text class="month-header" x="98" y="15">February 2024</text>
+            <text class="weekday-header" x="14" y="35">Su</text>
+            <text class="weekday-header" x="42" y="35">Mo</text>
+            <text class="weekday-header" x="70" y="35">Tu</text>
+            <text class="weekday-header" x="98" y="35">We</text>
+            <text class="weekday-header" x="126" y="35">Th</text>
+            <text class="weekday-header" x="154" y="35">Fr</text>
+            <text class="weekday-header" x="182" y="35">Sa</text>
+            <text class="day" x="126" y="55">1</text>
+            <text class="day" x="154" y="55">2</text>
+            <text class="day weekend" x="182" y="55">3</text>
+            <text class="day weekend" x="14" y="75">4</text>
+            <text class="day" x="42" y="75">5</text>
+            <text class="day" x="70" y="75">6</text>
+            <text class="day" x="98" y="75">7</text>
+            <text class="day" x="126" y="75">8</text>
+            <text class="day" x="154" y="75">9</text>
+            <text class="day weekend" x="182" y="75">10</text>
+            <text class="day weekend" x="14" y="95">11</text>
+            <text class="day" x="42" y="95">12</text>
+            <text class="day" x="70" y="95">13</text>
+            <text class="day" x="98" y="95">14</text>
+            <text class="day" x="126" y="95">15</text>
+            <text class="day" x="154" y="95">16</text>
+            <text class="day weekend" x="182" y="95">17</text>
+            <text class="day weekend" x="14" y="115">18</text>
+            <text class="day" x="42" y="115">19</text>
+            <text class="day today" x="70" y="115">20</text>
+            <text class="day" x="98" y="115">21</text>
+            <text class="day" x="126" y="115">22</text>
+            <text class="day" x="154" y="115">23</text>
+            <text class="day weekend" x="182" y="115">24</text>
+            <text class="day weekend" x="14" y="135">25</text>
+            <text class="day" x="42" y="135">26</text>
+            <text class="day" x="70" y="135">27</text>
+            <text class="day" x="98" y="135">28</text>
+            <text class="day" x="126" y="135">29</text>
+          </g>
+        </svg>
+        "###);
+    }
+
+    #[test]
+    fn test_print_svg_layout() {
+        let args = args(["cal", "2024", "--format", "svg", "--columns", "4"]);
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let output = print(args, current_date).unwrap();
+
+        // 4 columns and 3 rows of months
+        let width = 4 * SVG_MONTH_WIDTH + 3 * SVG_MONTH_GAP;
+        let height = 3 * SVG_MONTH_HEIGHT + 2 * SVG_MONTH_GAP;
+        assert!(output.contains(&format!("viewBox=\"0 0 {} {}\"", width, height)));
+        assert_eq!(output.matches("<g class=\"month\"").count(), 12);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1B[7m20\x1B[27m 21"), "20 21");
+        assert_eq!(strip_ansi("\x1B[1;33mMarch\x1B[22;39m"), "March");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+
+    #[test]
+    fn test_output_hash() {
+        // FNV-1a test vectors
+        assert_eq!(output_hash(""), "cbf29ce484222325");
+        assert_eq!(output_hash("a"), "af63dc4c8601ec8c");
+        assert_eq!(output_hash("\x1B[7ma\x1B[27m"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_print_verify() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+
+        let plain = {
+            let _color = force_color("0");
+            print(args(["cal", "-f", "sunday"]), current_date).unwrap()
+        };
+        let hash = output_hash(&plain);
+
+        // color codes don't affect the hash
+        let _color = force_color("1");
+
+        assert!(print(
+            args(["cal", "-f", "sunday", "--verify", &hash]),
+            current_date
+        )
+        .is_ok());
+        assert_eq!(
+            print(
+                args(["cal", "-f", "sunday", "--verify", "0000000000000000"]),
+                current_date
+            ),
+            Err(CalError::VerificationFailed {
+                expected: "0000000000000000".to_string(),
+                actual: hash
+            })
+        );
+
+        // other formats and queries are verified too
+        let mismatch = |argv: &[&str]| {
+            let argv = argv.iter().chain(&["--verify", "0000000000000000"]);
+
+            matches!(
+                print(args(argv.copied()), current_date),
+                Err(CalError::VerificationFailed { .. })
+            )
+        };
+        assert!(mismatch(&["cal", "-f", "sunday", "--format", "svg"]));
+        assert!(mismatch(&["cal", "--weekday-of", "2024-03-20"]));
+        assert!(mismatch(&["cal", "--count-weekday", "mon"]));
+
+        let svg = print(
+            args(["cal", "-f", "sunday", "--format", "svg"]),
+            current_date,
+        )
+        .unwrap();
+        let svg_hash = output_hash(&svg);
+        assert!(print(
+            args(["cal", "-f", "sunday", "--format", "svg", "--verify", &svg_hash]),
+            current_date
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_write_output() {
+        let dir =
+            std::env::temp_dir().join(format!("cal-test-write-output-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("calendar.txt");
+        write_output(&path, "March 2024").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "March 2024\n");
+
+        let missing = dir.join("missing").join("calendar.txt");
+        let err = write_output(&missing, "March 2024").unwrap_err();
+        assert!(matches!(&err, CalError::Io { path, .. } if *path == missing));
+        assert!(err
+            .to_string()
+            .starts_with(&format!("could not write {}: ", missing.display())));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_stdout() {
+        // accepts `capacity` bytes, then fails like a pipe whose reader has gone away
+        struct ClosedEarly {
+            written: Vec<u8>,
+            capacity: usize,
+            kind: std::io::ErrorKind,
+        }
+
+        impl std::io::Write for ClosedEarly {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                if self.written.len() >= self.capacity {
+                    return Err(std::io::Error::from(self.kind));
+                }
+
+                let len = buf.len().min(self.capacity - self.written.len());
+                self.written.extend_from_slice(&buf[..len]);
+
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let closed_early = |capacity, kind| ClosedEarly {
+            written: Vec::new(),
+            capacity,
+            kind,
+        };
+
+        let mut writer = closed_early(usize::MAX, std::io::ErrorKind::BrokenPipe);
+        write_stdout(&mut writer, "March 2024").unwrap();
+        assert_eq!(writer.written, b"March 2024\n");
+
+        let mut writer = closed_early(5, std::io::ErrorKind::BrokenPipe);
+        write_stdout(&mut writer, "March 2024").unwrap();
+        assert_eq!(writer.written, b"March");
+
+        let mut writer = closed_early(5, std::io::ErrorKind::PermissionDenied);
+        assert_eq!(
+            write_stdout(&mut writer, "March 2024").unwrap_err().kind(),
+            std::io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn test_parse_highlight_file() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let highlighted = |color, label: Option<&str>| HighlightedDate {
+            color,
+            label: label.map(str::to_string),
+        };
+
+        let dates = parse_highlight_file(
+            "# deadlines\n\
+             2024-03-01\n\
+             2024-03-04,196\n\
+             2024-03-05, Birthday\n\
+             \n\
+             2024-03-06,#ff0000,Taxes, finally\n\
+             2024-03-07,,Dentist\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            dates.into_iter().collect_vec(),
+            vec![
+                (date(1), highlighted(None, None)),
+                (date(4), highlighted(Some(Color::Indexed(196)), None)),
+                (date(5), highlighted(None, Some("Birthday"))),
+                (
+                    date(6),
+                    highlighted(Some(Color::Rgb(255, 0, 0)), Some("Taxes, finally"))
+                ),
+                (date(7), highlighted(None, Some("Dentist"))),
+            ]
+        );
+
+        assert_eq!(
+            parse_highlight_file("2024-03-01\n2024-03-32,red"),
+            Err("line 2: Invalid date (expected YYYY-MM-DD): 2024-03-32".to_string())
+        );
+        assert_eq!(
+            parse_highlight_file("2024-03-01,red,Birthday"),
+            Err(
+                "line 1: Invalid color (expected a palette index 0 - 255 or #RRGGBB): red"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_print_highlight_file() {
+        let dir =
+            std::env::temp_dir().join(format!("cal-test-highlight-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("dates.txt");
+        std::fs::write(
+            &path,
+            "2024-03-08,201,Birthday\n2024-03-15,#ff0000\n2024-03-22\n",
+        )
+        .unwrap();
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let highlighted = || {
+            ["cal", "-f", "sunday", "2024-03", "--highlight-file"]
+                .map(OsString::from)
+                .into_iter()
+                .chain([path.clone().into_os_string()])
+                .collect_vec()
+        };
+        let plain = {
+            let _color = force_color("0");
+            print(args(highlighted()), current_date).unwrap()
+        };
+        insta::assert_snapshot!(show_edges(&plain), @r###"
+        |      March 2024      |
+        | Su Mo Tu We Th Fr Sa |
+        |                 1  2 |
+        |  3  4  5  6  7[ 8] 9 |
+        | 10 11 12 13 14[15]16 |
+        | 17 18 19 20 21[22]23 |
+        | 24 25 26 27 28 29 30 |
+        | 31                   |
+        ||
+        |2024-03-08  Birthday|
+        "###);
+
+        let color = {
+            // `force_color` pins the 256-color palette, which RGB colors are approximated with
+            let _color = force_color("1");
+            print(args(highlighted()), current_date).unwrap()
+        };
+        insta::assert_snapshot!(escape_ansi(&color), @r###"
+             March 2024     
+        Su Mo Tu We Th Fr Sa
+                        1  2
+         3  4  5  6  7 \e[38;5;201m 8\e[39m  9
+        10 11 12 13 14 \e[38;5;196m15\e[39m 16
+        17 18 19 20 21 \e[4m22\e[24m 23
+        24 25 26 27 28 29 30
+        31                  
+
+        \e[38;5;201m2024-03-08\e[39m  Birthday
+        "###);
+
+        // `--highlight-style` marks highlighted dates the same way as today
+        let styled = |style: &str| {
+            let _color = force_color("1");
+            let argv = highlighted()
+                .into_iter()
+                .chain(["--highlight-style", style].map(OsString::from))
+                .collect_vec();
+
+            escape_ansi(&print(args(argv), current_date).unwrap())
+        };
+        insta::assert_snapshot!(styled("bold"), @r###"
+             March 2024     
+        Su Mo Tu We Th Fr Sa
+                        1  2
+         3  4  5  6  7 \e[1m 8\e[22m  9
+        10 11 12 13 14 \e[1m15\e[22m 16
+        17 18 19 20 21 \e[1m22\e[22m 23
+        24 25 26 27 28 29 30
+        31                  
+
+        \e[1m2024-03-08\e[22m  Birthday
+        "###);
+        insta::assert_snapshot!(show_edges(&styled("box")), @r###"
+        |      March 2024      |
+        | Su Mo Tu We Th Fr Sa |
+        |                 1  2 |
+        |  3  4  5  6  7[ 8] 9 |
+        | 10 11 12 13 14[15]16 |
+        | 17 18 19 20 21[22]23 |
+        | 24 25 26 27 28 29 30 |
+        | 31                   |
+        ||
+        |2024-03-08  Birthday|
+        "###);
+
+        let missing = dir.join("missing.txt");
+        let err = print(
+            args([
+                OsString::from("cal"),
+                "--highlight-file".into(),
+                missing.clone().into(),
+            ]),
+            current_date,
+        )
+        .unwrap_err();
+        assert!(matches!(&err, CalError::Read { path, .. } if *path == missing));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_print_overlay_notes() {
+        let _color = force_color("0");
+
+        let dir =
+            std::env::temp_dir().join(format!("cal-test-overlay-notes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("notes.txt");
+        std::fs::write(
+            &path,
+            "2024-03-08,Dentist\n2024-03-22,#ff0000,Taxes due\n2024-04-01\n",
+        )
+        .unwrap();
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 5, 20).unwrap();
+        let notes = |argv: &[&str]| {
+            argv.iter()
+                .map(OsString::from)
+                .chain([OsString::from("--overlay-notes"), path.clone().into()])
+                .collect_vec()
+        };
+
+        // the date without a label is only marked, the months without notes are unchanged
+        let march = print(
+            args(notes(&["cal", "-f", "sunday", "2024-03", "-A", "1"])),
+            current_date,
+        )
+        .unwrap();
+        insta::assert_snapshot!(show_edges(&march), @r###"
+        |      March 2024         8  Dentist|
+        | Su Mo Tu We Th Fr Sa   22  Taxes due|
+        |                 1  2 |
+        |  3  4  5  6  7[ 8] 9 |
+        | 10 11 12 13 14 15 16 |
+        | 17 18 19 20 21[22]23 |
+        | 24 25 26 27 28 29 30 |
+        | 31                   |
+        ||
+        |      April 2024      |
+        | Su Mo Tu We Th Fr Sa |
+        |   [ 1] 2  3  4  5  6 |
+        |  7  8  9 10 11 12 13 |
+        | 14 15 16 17 18 19 20 |
+        | 21 22 23 24 25 26 27 |
+        | 28 29 30             |
+        |                      |
+        "###);
+
+        // notes that don't fit beside the month continue below it
+        std::fs::write(
+            &path,
+            (1..=8)
+                .map(|day| format!("2024-02-{:02},Note {}\n", day * 3, day))
+                .collect::<String>(),
+        )
+        .unwrap();
+        let february = print(
+            args(notes(&["cal", "-f", "sunday", "2024-02"])),
+            current_date,
+        )
+        .unwrap();
+        insta::assert_snapshot!(show_edges(&february), @r###"
+        |    February 2024        3  Note 1|
+        | Su Mo Tu We Th Fr Sa    6  Note 2|
+        |              1  2[ 3]   9  Note 3|
+        |  4  5[ 6] 7  8[ 9]10   12  Note 4|
+        | 11[12]13 14[15]16 17   15  Note 5|
+        |[18]19 20[21]22 23[24]  18  Note 6|
+        | 25 26 27 28 29         21  Note 7|
+        |                        24  Note 8|
+        "###);
+
+        assert!(Arguments::try_parse_from(notes(&["cal", "--columns", "2"])).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_print_max_months() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let long_range = [
+            "cal",
+            "-f",
+            "sunday",
+            "--start-date",
+            "2024-01",
+            "--num-months",
+            "132",
+        ];
+
+        assert_eq!(
+            print(args(long_range), current_date),
+            Err(CalError::TooManyMonths {
+                months: 132,
+                max_months: 60
+            })
+        );
+        assert!(print(args(long_range.iter().chain(&["--force"])), current_date).is_ok());
+        assert!(print(
+            args(["cal", "2023", "2024", "-f", "sunday", "--max-months", "24"]),
+            current_date
+        )
+        .is_ok());
+        assert!(print(
+            args(["cal", "2023", "2024", "-f", "sunday", "--max-months", "23"]),
+            current_date
+        )
+        .is_err());
+        assert_eq!(
+            CalError::TooManyMonths {
+                months: 132,
+                max_months: 60
+            }
+            .to_string(),
+            "132 months would be displayed, which is more than --max-months 60 (use --force to display them anyway)"
+        );
+    }
+
+    #[test]
+    fn test_explanation() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let current_date = date(2024, 5, 20);
+        let parsed_input = vec![
+            parse_date_input("FYQ3").unwrap(),
+            parse_date_input("24-12").unwrap(),
+        ];
+        let resolved_input: Vec<_> = parsed_input
+            .iter()
+            .map(|date_input| {
+                resolve_date_input(current_date, 7, None, date_input.clone()).unwrap()
+            })
+            .collect();
+        let date_ranges = determine_date_ranges(
+            current_date,
+            Arguments {
+                date_input: resolved_input.clone(),
+                ..args(["cal"])
+            },
+        )
+        .unwrap();
+
+        insta::assert_snapshot!(
+            explanation(
+                &parsed_input,
+                &resolved_input,
+                (Weekday::Sun, FirstDayOfWeekSource::Argument),
+                &date_ranges
+            ),
+            @r###"
+        input: CurrentYearQuarter(Fiscal, Q3)
+          resolved to: YearQuarter(Year { style: Fiscal, year: 2024 }, Q3)
+        input: YearMonth(Year { style: Calendar, year: 24 }, 12)
+          resolved to: YearMonth(Year { style: Calendar, year: 2024 }, 12)
+        first day of week: Sun (set by --first-day-of-week)
+        range: 2024-01-01 through 2024-03-31
+        range: 2024-12-01 through 2024-12-31
+        "###
+        );
+        insta::assert_snapshot!(
+            explanation(
+                &[],
+                &[],
+                (Weekday::Mon, FirstDayOfWeekSource::Fallback),
+                &[(date(2024, 5, 1), date(2024, 5, 31))]
+            ),
+            @r###"
+        input: none
+        first day of week: Mon (default, no preference was detected)
+        range: 2024-05-01 through 2024-05-31
+        "###
+        );
+
+        // `--explain` hands the explanation to the notices, leaving the calendar alone
+        let _color = force_color("0");
+        let mut notices = Vec::new();
+        let output = print_with_notices(
+            args(["cal", "-f", "sunday", "--explain", "--quiet", "24-12"]),
+            current_date,
+            &mut |message| notices.push(message),
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            print(args(["cal", "-f", "sunday", "24-12"]), current_date).unwrap()
+        );
+        insta::assert_snapshot!(notices.join("\n"), @r###"
+        input: YearMonth(Year { style: Calendar, year: 24 }, 12)
+          resolved to: YearMonth(Year { style: Calendar, year: 2024 }, 12)
+        first day of week: Sun (set by --first-day-of-week)
+        range: 2024-12-01 through 2024-12-31
+        "###);
+    }
+
+    #[test]
+    fn test_count_months() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(count_months(&[(date(2024, 3, 31), date(2024, 4, 1))]), 2);
+        assert_eq!(count_months(&[(date(2023, 12, 1), date(2025, 1, 31))]), 14);
+        // overlapping months are only counted once
+        assert_eq!(
+            count_months(&[
+                (date(2024, 1, 1), date(2024, 3, 31)),
+                (date(2024, 3, 1), date(2024, 4, 30)),
+                (date(2024, 12, 1), date(2024, 12, 31)),
+            ]),
+            5
+        );
+    }
+
+    #[test]
+    fn test_print_empty_range() {
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let start_date = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        let end_date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert!(build_month_range(start_date, end_date, Weekday::Sun)
+            .months
+            .is_empty());
+        assert!(build_month(vec![], Weekday::Sun).is_none());
+
+        let args = Arguments {
+            date_input: vec![DateInput::Range(start_date, end_date)],
+            ..args(["cal", "-f", "sunday"])
+        };
+        assert_eq!(print(args, current_date), Err(CalError::EmptyRange));
+        assert_eq!(CalError::EmptyRange.to_string(), "no months in range");
+    }
+
+    #[test]
+    fn test_month_print_sun_first() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--first-day-of-week", "sunday"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+             March 2024     
+        Su Mo Tu We Th Fr Sa
+                        1  2
+         3  4  5  6  7  8  9
+        10 11 12 13 14 15 16
+        17 18 19 20 21 22 23
+        24 25 26 27 28 29 30
+        31                  
+        "###);
+    }
+
+    #[test]
+    fn test_build_month_leap_february() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 2, 20).unwrap();
+        let args = args(["cal"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           February 2024    
+        Mo Tu We Th Fr Sa Su
+                  1  2  3  4
+         5  6  7  8  9 10 11
+        12 13 14 15 16 17 18
+        19 20 21 22 23 24 25
+        26 27 28 29         
+        "###);
+    }
+
+    #[test]
+    fn test_month_range_print_simple() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "-B", "1", "-A", "1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           February 2024           March 2024            April 2024     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+                  1  2  3  4               1  2  3   1  2  3  4  5  6  7
+         5  6  7  8  9 10 11   4  5  6  7  8  9 10   8  9 10 11 12 13 14
+        12 13 14 15 16 17 18  11 12 13 14 15 16 17  15 16 17 18 19 20 21
+        19 20 21 22 23 24 25  18 19 20 21 22 23 24  22 23 24 25 26 27 28
+        26 27 28 29           25 26 27 28 29 30 31  29 30               
+        "###);
+    }
+
+    #[test]
+    fn test_month_range_print_long_args() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2023, 3, 20).unwrap();
+        let args = args(["cal", "--months-before", "1", "--months-after", "1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           February 2023           March 2023            April 2023     
+        Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su  Mo Tu We Th Fr Sa Su
+               1  2  3  4  5         1  2  3  4  5                  1  2
+         6  7  8  9 10 11 12   6  7  8  9 10 11 12   3  4  5  6  7  8  9
+        13 14 15 16 17 18 19  13 14 15 16 17 18 19  10 11 12 13 14 15 16
+        20 21 22 23 24 25 26  20 21 22 23 24 25 26  17 18 19 20 21 22 23
+        27 28                 27 28 29 30 31        24 25 26 27 28 29 30
+        "###);
+    }
+
+    #[test]
+    fn test_month_range_print_sun_first() {
+        let _color = force_color("0");
+
+        let current_date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let args = args(["cal", "--first-day-of-week", "sunday", "-B", "1", "-A", "1"]);
+
+        insta::assert_snapshot!(print(args, current_date).unwrap(), @r###"
+           February 2024           March 2024            April 2024     
+        Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa  Su Mo Tu We Th Fr Sa
+                     1  2  3                  1  2      1  2  3  4  5  6
+         4  5  6  7  8  9 10   3  4  5  6  7  8  9   7  8  9 10 11 12 13
+        11 12 13 14 15 16 17  10 11 12 13 14 15 16  14 15 16 17 18 19 20
+        18 19 20 21 22 23 24  17 18 19 20 21 22 23  21 22 23 24 25 26 27
+        25 26 27 28 29        24 25 26 27 28 29 30  28 29 30            
+                              31                                        
+        "###);
+    }
+}